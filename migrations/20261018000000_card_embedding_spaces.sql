@@ -0,0 +1,28 @@
+-- Named embedding spaces, one per embedding model, so vectors from several
+-- models can be stored side by side and compared
+CREATE TABLE IF NOT EXISTS embedding_space (
+    model_id text PRIMARY KEY,
+    dimension integer NOT NULL CHECK (dimension > 0),
+    created_at timestamptz NOT NULL DEFAULT now(),
+    UNIQUE (model_id, dimension)
+);
+
+-- The vectors need the pgvector extension. Without it the table is skipped and
+-- named spaces stay unavailable, as vector queries then run in memory over
+-- card.embedding only
+DO $$
+BEGIN
+    IF EXISTS (SELECT 1 FROM pg_extension WHERE extname = 'vector') THEN
+        CREATE TABLE IF NOT EXISTS card_embedding (
+            card_id integer NOT NULL REFERENCES card (id) ON DELETE CASCADE,
+            model_id text NOT NULL,
+            dimension integer NOT NULL,
+            embedding vector NOT NULL CHECK (vector_dims(embedding) = dimension),
+            updated_at timestamptz NOT NULL DEFAULT now(),
+            PRIMARY KEY (model_id, card_id),
+            FOREIGN KEY (model_id, dimension)
+                REFERENCES embedding_space (model_id, dimension) ON DELETE CASCADE
+        );
+    END IF;
+END
+$$;
@@ -0,0 +1,8 @@
+-- Full-text index for hybrid search. The expression must match
+-- LEXICAL_DOCUMENT in src/cards/repository.rs for the planner to use it.
+CREATE INDEX IF NOT EXISTS card_lexical_document_idx ON card USING gin (
+    to_tsvector(
+        'english',
+        coalesce(name, '') || ' ' || coalesce(type_line, '') || ' ' || coalesce(oracle_text, '')
+    )
+);
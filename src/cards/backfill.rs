@@ -0,0 +1,109 @@
+use super::model::CardModel;
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+/// Which cards an embedding backfill recomputes
+#[derive(
+    Debug, Clone, Copy, Default, Deserialize, Serialize, PartialEq, Eq, schemars::JsonSchema,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum BackfillMode {
+    /// Cards without an embedding
+    #[default]
+    Missing,
+    /// Cards without an embedding or whose embedding has a different dimension
+    /// than the embedder's, i.e. was produced by another model. In a named
+    /// embedding space, whose dimension is fixed, the same as `Missing`.
+    Stale,
+    /// Every card
+    All,
+}
+
+impl BackfillMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BackfillMode::Missing => "missing",
+            BackfillMode::Stale => "stale",
+            BackfillMode::All => "all",
+        }
+    }
+}
+
+impl FromStr for BackfillMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "missing" => Ok(BackfillMode::Missing),
+            "stale" => Ok(BackfillMode::Stale),
+            "all" => Ok(BackfillMode::All),
+            other => Err(format!(
+                "Unknown backfill mode '{}' (expected missing, stale or all)",
+                other
+            )),
+        }
+    }
+}
+
+/// Options for an embedding backfill run
+#[derive(Debug, Clone)]
+pub struct BackfillOptions {
+    pub mode: BackfillMode,
+    /// Cards embedded and written per transaction
+    pub batch_size: i64,
+    /// Only process cards with a greater ID, to resume an interrupted run
+    pub after_id: Option<i32>,
+    /// Stop after this many cards
+    pub max_cards: Option<i64>,
+    /// Store vectors in the embedder's named embedding space, recording the
+    /// model that produced them, instead of `card.embedding`
+    pub named_space: bool,
+}
+
+impl Default for BackfillOptions {
+    fn default() -> Self {
+        Self {
+            mode: BackfillMode::Missing,
+            batch_size: 100,
+            after_id: None,
+            max_cards: None,
+            named_space: false,
+        }
+    }
+}
+
+/// Progress of a backfill run, reported after every batch
+#[derive(Debug, Clone, Default, Serialize, schemars::JsonSchema)]
+pub struct BackfillProgress {
+    /// Cards matching the backfill mode when the run started
+    pub total: i64,
+    /// Cards processed so far
+    pub processed: i64,
+    /// Cards whose embedding was written
+    pub updated: i64,
+    /// Cards that could not be embedded
+    pub failed: i64,
+    /// ID of the last processed card; pass it as `after_id` to resume
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_id: Option<i32>,
+    /// Whether every matching card has been processed
+    pub done: bool,
+}
+
+/// Text a card is embedded from: name, type line, mana cost, keywords and
+/// oracle text
+pub fn embedding_text(card: &CardModel) -> String {
+    let keywords = card.keywords.as_ref().map(|keywords| keywords.join(", "));
+    [
+        Some(card.name.as_str()),
+        card.type_line.as_deref(),
+        card.mana_cost.as_deref(),
+        keywords.as_deref(),
+        card.oracle_text.as_deref(),
+    ]
+    .into_iter()
+    .flatten()
+    .filter(|part| !part.is_empty())
+    .collect::<Vec<_>>()
+    .join("\n")
+}
@@ -0,0 +1,643 @@
+use super::model::{CardModel, ManaPips};
+use crate::similarity::vector::cosine_similarity;
+use serde::Serialize;
+use std::collections::HashSet;
+
+/// How a candidate card relates to the card it is compared against
+#[derive(Debug, Clone, PartialEq)]
+pub enum Comparison {
+    /// Same rules text, cost, stats and keywords under a different name
+    FunctionalReprint,
+    /// At least as good in every respect and better in the listed ways
+    StrictlyBetter(Vec<String>),
+}
+
+#[derive(Debug, Default)]
+pub struct BetterCards {
+    pub strictly_better: Vec<(CardModel, Vec<String>)>,
+    pub functional_reprints: Vec<CardModel>,
+}
+
+/// Normalizes oracle text for comparison across cards: self-references become
+/// `cardname`, reminder text is dropped, and case and whitespace are folded.
+/// Returns one entry per ability line.
+pub fn normalize_oracle_text(card: &CardModel) -> Vec<String> {
+    let Some(text) = card.oracle_text.as_deref() else {
+        return Vec::new();
+    };
+
+    let mut text = text.replace(&card.name, "CARDNAME");
+    if let Some((short_name, _)) = card.name.split_once(", ") {
+        text = text.replace(short_name, "CARDNAME");
+    }
+    let self_reference = format!("this {}", card.main_type.as_str().to_lowercase());
+
+    text.lines()
+        .map(|line| {
+            let line = strip_reminder_text(line).to_lowercase();
+            let line = line.replace(&self_reference, "cardname");
+            line.split_whitespace().collect::<Vec<_>>().join(" ")
+        })
+        .filter(|line| !line.is_empty())
+        .collect()
+}
+
+fn strip_reminder_text(line: &str) -> String {
+    let mut depth = 0usize;
+    line.chars()
+        .filter(|c| match c {
+            '(' => {
+                depth += 1;
+                false
+            }
+            ')' => {
+                depth = depth.saturating_sub(1);
+                false
+            }
+            _ => depth == 0,
+        })
+        .collect()
+}
+
+fn keyword_set(card: &CardModel) -> HashSet<String> {
+    card.keywords
+        .iter()
+        .flatten()
+        .map(|keyword| keyword.to_lowercase())
+        .collect()
+}
+
+/// Ability lines with keyword-only lines ("Flying, haste") removed, since
+/// keywords are compared separately. Lines containing a sentence are always
+/// kept so that spell text like "Scry 2. Draw a card." is still compared.
+fn rules_lines(card: &CardModel, keywords: &HashSet<String>) -> Vec<String> {
+    normalize_oracle_text(card)
+        .into_iter()
+        .filter(|line| {
+            line.contains('.')
+                || !line.split([',', ';']).all(|part| {
+                    let part = part.trim();
+                    keywords.iter().any(|keyword| {
+                        part == keyword || part.starts_with(&format!("{} ", keyword))
+                    })
+                })
+        })
+        .collect()
+}
+
+fn pips(card: &CardModel) -> ManaPips {
+    card.mana_cost
+        .as_deref()
+        .map(ManaPips::from_mana_cost)
+        .unwrap_or_default()
+}
+
+/// Compares power or toughness. Non-numeric values such as `*` only match
+/// themselves.
+fn compare_stat(candidate: &Option<String>, target: &Option<String>) -> Option<std::cmp::Ordering> {
+    match (candidate, target) {
+        (None, None) => Some(std::cmp::Ordering::Equal),
+        (Some(candidate), Some(target)) => {
+            match (candidate.parse::<i32>(), target.parse::<i32>()) {
+                (Ok(candidate), Ok(target)) => Some(candidate.cmp(&target)),
+                _ if candidate == target => Some(std::cmp::Ordering::Equal),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Determines whether `candidate` is strictly better than, or a functional
+/// reprint of, `target`. Returns `None` when the cards are not comparable or
+/// the candidate is worse in any respect.
+pub fn compare(target: &CardModel, candidate: &CardModel) -> Option<Comparison> {
+    use std::cmp::Ordering;
+
+    if candidate.main_type != target.main_type {
+        return None;
+    }
+
+    let is_legendary = |card: &CardModel| {
+        card.type_line
+            .as_deref()
+            .unwrap_or("")
+            .contains("Legendary")
+    };
+    if is_legendary(candidate) != is_legendary(target) {
+        return None;
+    }
+
+    let target_keywords = keyword_set(target);
+    let candidate_keywords = keyword_set(candidate);
+    if !candidate_keywords.is_superset(&target_keywords) {
+        return None;
+    }
+    if rules_lines(candidate, &candidate_keywords) != rules_lines(target, &target_keywords) {
+        return None;
+    }
+
+    let target_pips = pips(target);
+    let candidate_pips = pips(candidate);
+    if candidate_pips
+        .by_symbol()
+        .iter()
+        .zip(target_pips.by_symbol().iter())
+        .any(|((_, candidate), (_, target))| candidate > target)
+    {
+        return None;
+    }
+
+    let target_cmc = target.cmc.unwrap_or(0.0);
+    let candidate_cmc = candidate.cmc.unwrap_or(0.0);
+    if candidate_cmc > target_cmc {
+        return None;
+    }
+
+    let power = compare_stat(&candidate.power, &target.power)?;
+    let toughness = compare_stat(&candidate.toughness, &target.toughness)?;
+    if power == Ordering::Less || toughness == Ordering::Less {
+        return None;
+    }
+
+    let mut reasons = Vec::new();
+    if candidate_cmc < target_cmc {
+        reasons.push(format!(
+            "lower mana value ({} vs {})",
+            candidate_cmc, target_cmc
+        ));
+    } else if candidate_pips != target_pips {
+        reasons.push("fewer colored mana symbols".to_string());
+    }
+    if power == Ordering::Greater {
+        reasons.push(format!(
+            "higher power ({} vs {})",
+            candidate.power.as_deref().unwrap_or_default(),
+            target.power.as_deref().unwrap_or_default()
+        ));
+    }
+    if toughness == Ordering::Greater {
+        reasons.push(format!(
+            "higher toughness ({} vs {})",
+            candidate.toughness.as_deref().unwrap_or_default(),
+            target.toughness.as_deref().unwrap_or_default()
+        ));
+    }
+    let mut extra_keywords: Vec<&String> = candidate
+        .keywords
+        .iter()
+        .flatten()
+        .filter(|keyword| !target_keywords.contains(&keyword.to_lowercase()))
+        .collect();
+    if !extra_keywords.is_empty() {
+        extra_keywords.sort();
+        reasons.push(format!(
+            "additional keywords: {}",
+            extra_keywords
+                .iter()
+                .map(|keyword| keyword.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        ));
+    }
+
+    if reasons.is_empty() {
+        Some(Comparison::FunctionalReprint)
+    } else {
+        Some(Comparison::StrictlyBetter(reasons))
+    }
+}
+
+/// Two cards and what they have in common
+#[derive(Debug)]
+pub struct CardComparison {
+    pub first: CardModel,
+    pub second: CardModel,
+    pub explanation: SimilarityExplanation,
+}
+
+/// Values two cards share and the ones unique to each
+#[derive(Debug, Clone, Default, Serialize, schemars::JsonSchema)]
+pub struct Overlap {
+    pub shared: Vec<String>,
+    pub only_first: Vec<String>,
+    pub only_second: Vec<String>,
+}
+
+impl Overlap {
+    /// Compares two lists case-insensitively, keeping the first spelling seen and
+    /// the order of the input lists
+    fn between<'a>(
+        first: impl IntoIterator<Item = &'a str>,
+        second: impl IntoIterator<Item = &'a str>,
+    ) -> Self {
+        let dedup = |values: &mut Vec<&'a str>| {
+            let mut seen = HashSet::new();
+            values.retain(|value| seen.insert(value.to_lowercase()));
+        };
+        let mut first: Vec<&str> = first.into_iter().collect();
+        let mut second: Vec<&str> = second.into_iter().collect();
+        dedup(&mut first);
+        dedup(&mut second);
+
+        let first_set: HashSet<String> = first.iter().map(|v| v.to_lowercase()).collect();
+        let second_set: HashSet<String> = second.iter().map(|v| v.to_lowercase()).collect();
+        Self {
+            shared: first
+                .iter()
+                .filter(|v| second_set.contains(&v.to_lowercase()))
+                .map(|v| v.to_string())
+                .collect(),
+            only_first: first
+                .iter()
+                .filter(|v| !second_set.contains(&v.to_lowercase()))
+                .map(|v| v.to_string())
+                .collect(),
+            only_second: second
+                .iter()
+                .filter(|v| !first_set.contains(&v.to_lowercase()))
+                .map(|v| v.to_string())
+                .collect(),
+        }
+    }
+}
+
+/// Concrete attributes two cards have in common, to explain their embedding
+/// similarity
+#[derive(Debug, Clone, Serialize, schemars::JsonSchema)]
+pub struct SimilarityExplanation {
+    /// Cosine similarity of the card embeddings, if both have one
+    pub embedding_similarity: Option<f64>,
+    /// Supertypes, types and subtypes from the type lines
+    pub type_line: Overlap,
+    pub keywords: Overlap,
+    pub colors: Overlap,
+    /// Mana value of the second card minus that of the first
+    pub cmc_delta: f64,
+    /// Oracle text phrases of at least three words found on both cards, longest
+    /// first, with self-references normalized to "cardname"
+    pub shared_phrases: Vec<String>,
+}
+
+/// Shortest phrase, in words, reported as shared oracle text
+const MIN_PHRASE_WORDS: usize = 3;
+
+/// Words of the normalized oracle text with surrounding punctuation removed.
+/// Lines are separated by `boundary` so phrases never span abilities.
+fn phrase_words(card: &CardModel, boundary: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    for line in normalize_oracle_text(card) {
+        words.extend(
+            line.split_whitespace()
+                .map(|word| word.trim_matches(|c: char| !c.is_alphanumeric() && c != '+'))
+                .filter(|word| !word.is_empty())
+                .map(str::to_string),
+        );
+        words.push(boundary.to_string());
+    }
+    words
+}
+
+/// Maximal runs of words appearing in both oracle texts, longest first. Phrases
+/// contained in a longer shared phrase are omitted.
+pub fn shared_phrases(first: &CardModel, second: &CardModel) -> Vec<String> {
+    // Distinct boundaries so a line break on one card never matches the other's
+    let first_words = phrase_words(first, "\n1");
+    let second_words = phrase_words(second, "\n2");
+
+    // previous[j] is the length of the common run ending at the previous first
+    // word and second_words[j - 1]. Both word lists end with a boundary, so every
+    // run is followed by a mismatch and gets collected.
+    let mut phrases: Vec<String> = Vec::new();
+    let mut previous = vec![0usize; second_words.len() + 1];
+    for i in 1..=first_words.len() {
+        let mut current = vec![0usize; second_words.len() + 1];
+        for j in 1..=second_words.len() {
+            if first_words[i - 1] == second_words[j - 1] {
+                current[j] = previous[j - 1] + 1;
+            }
+        }
+        // A run ends where it cannot be extended by the next pair of words
+        for j in 1..=second_words.len() {
+            let length = previous[j - 1];
+            let extended = first_words[i - 1] == second_words[j - 1];
+            if length >= MIN_PHRASE_WORDS && !extended {
+                phrases.push(first_words[i - 1 - length..i - 1].join(" "));
+            }
+        }
+        previous = current;
+    }
+
+    phrases.sort_by(|a, b| b.len().cmp(&a.len()).then_with(|| a.cmp(b)));
+    phrases.dedup();
+    let mut maximal: Vec<String> = Vec::new();
+    for phrase in phrases {
+        let padded = format!(" {} ", phrase);
+        if !maximal
+            .iter()
+            .any(|longer| format!(" {} ", longer).contains(&padded))
+        {
+            maximal.push(phrase);
+        }
+    }
+    maximal
+}
+
+/// Type line words, e.g. "Legendary Creature — Elf Druid" gives Legendary,
+/// Creature, Elf and Druid
+fn type_words(card: &CardModel) -> Vec<&str> {
+    card.type_line
+        .as_deref()
+        .unwrap_or("")
+        .split_whitespace()
+        .filter(|word| *word != "—" && *word != "//")
+        .collect()
+}
+
+/// Explains what two cards have in common beyond their embedding similarity
+pub fn explain_similarity(first: &CardModel, second: &CardModel) -> SimilarityExplanation {
+    let embedding_similarity = match (&first.embedding, &second.embedding) {
+        (Some(a), Some(b)) => Some(cosine_similarity(a.as_slice(), b.as_slice()) as f64),
+        _ => None,
+    };
+    let strings =
+        |values: &Option<Vec<String>>| -> Vec<String> { values.clone().unwrap_or_default() };
+    let first_keywords = strings(&first.keywords);
+    let second_keywords = strings(&second.keywords);
+    let first_colors = strings(&first.colors);
+    let second_colors = strings(&second.colors);
+
+    SimilarityExplanation {
+        embedding_similarity,
+        type_line: Overlap::between(type_words(first), type_words(second)),
+        keywords: Overlap::between(
+            first_keywords.iter().map(String::as_str),
+            second_keywords.iter().map(String::as_str),
+        ),
+        colors: Overlap::between(
+            first_colors.iter().map(String::as_str),
+            second_colors.iter().map(String::as_str),
+        ),
+        cmc_delta: second.cmc.unwrap_or(0.0) - first.cmc.unwrap_or(0.0),
+        shared_phrases: shared_phrases(first, second),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cards::model::CardType;
+    use pgvector::Vector;
+
+    fn creature(name: &str, mana_cost: &str, cmc: f64, power: &str, toughness: &str) -> CardModel {
+        CardModel {
+            id: 0,
+            name: name.to_string(),
+            main_type: CardType::Creature,
+            type_line: Some("Creature — Ogre".to_string()),
+            oracle_text: None,
+            keywords: Some(Vec::new()),
+            cmc: Some(cmc),
+            mana_cost: Some(mana_cost.to_string()),
+            colors: Some(vec!["R".to_string()]),
+            color_identity: Some(vec!["R".to_string()]),
+            power: Some(power.to_string()),
+            toughness: Some(toughness.to_string()),
+            games: None,
+            legalities: None,
+            reserved: None,
+            game_changer: None,
+            embedding: None,
+        }
+    }
+
+    fn spell(name: &str, mana_cost: &str, cmc: f64, oracle_text: &str) -> CardModel {
+        CardModel {
+            main_type: CardType::Sorcery,
+            type_line: Some("Sorcery".to_string()),
+            oracle_text: Some(oracle_text.to_string()),
+            power: None,
+            toughness: None,
+            ..creature(name, mana_cost, cmc, "0", "0")
+        }
+    }
+
+    fn with_keywords(mut card: CardModel, keywords: &[&str]) -> CardModel {
+        card.keywords = Some(keywords.iter().map(|keyword| keyword.to_string()).collect());
+        card.oracle_text = Some(keywords.join(", "));
+        card
+    }
+
+    fn gray_ogre() -> CardModel {
+        creature("Gray Ogre", "{2}{R}", 3.0, "2", "2")
+    }
+
+    #[test]
+    fn higher_power_is_strictly_better() {
+        let candidate = creature("Bigger Ogre", "{2}{R}", 3.0, "3", "2");
+        assert_eq!(
+            compare(&gray_ogre(), &candidate),
+            Some(Comparison::StrictlyBetter(vec![
+                "higher power (3 vs 2)".to_string()
+            ]))
+        );
+    }
+
+    #[test]
+    fn cheaper_and_fewer_pips_are_strictly_better() {
+        let cheaper = creature("Cheap Ogre", "{1}{R}", 2.0, "2", "2");
+        assert_eq!(
+            compare(&gray_ogre(), &cheaper),
+            Some(Comparison::StrictlyBetter(vec![
+                "lower mana value (2 vs 3)".to_string()
+            ]))
+        );
+        let generic = creature("Colorless Ogre", "{3}", 3.0, "2", "2");
+        assert_eq!(
+            compare(&gray_ogre(), &generic),
+            Some(Comparison::StrictlyBetter(vec![
+                "fewer colored mana symbols".to_string()
+            ]))
+        );
+    }
+
+    #[test]
+    fn additional_keywords_are_strictly_better() {
+        let target = with_keywords(gray_ogre(), &["Trample"]);
+        let candidate = with_keywords(
+            creature("Hasty Ogre", "{2}{R}", 3.0, "2", "2"),
+            &["Trample", "Haste"],
+        );
+        assert_eq!(
+            compare(&target, &candidate),
+            Some(Comparison::StrictlyBetter(vec![
+                "additional keywords: Haste".to_string()
+            ]))
+        );
+        // Losing a keyword makes the candidate incomparable
+        assert_eq!(compare(&candidate, &target), None);
+    }
+
+    #[test]
+    fn worse_cards_are_rejected() {
+        let weaker = creature("Weak Ogre", "{2}{R}", 3.0, "1", "3");
+        assert_eq!(compare(&gray_ogre(), &weaker), None);
+        let pricier = creature("Pricey Ogre", "{3}{R}", 4.0, "3", "3");
+        assert_eq!(compare(&gray_ogre(), &pricier), None);
+        let more_pips = creature("Devoted Ogre", "{1}{R}{R}", 3.0, "2", "2");
+        assert_eq!(compare(&gray_ogre(), &more_pips), None);
+    }
+
+    #[test]
+    fn same_card_under_another_name_is_a_functional_reprint() {
+        let mut target = gray_ogre();
+        target.oracle_text =
+            Some("When Gray Ogre enters, it deals 1 damage to any target.".to_string());
+        let mut candidate = creature("Ogre Pyromancer", "{2}{R}", 3.0, "2", "2");
+        candidate.oracle_text = Some(
+            "When this creature enters, it deals 1 damage to any target. (Reminder text is ignored.)"
+                .to_string(),
+        );
+        assert_eq!(
+            compare(&target, &candidate),
+            Some(Comparison::FunctionalReprint)
+        );
+    }
+
+    #[test]
+    fn different_rules_text_or_legendary_status_is_incomparable() {
+        let mut candidate = creature("Other Ogre", "{2}{R}", 3.0, "3", "3");
+        candidate.oracle_text = Some("Other Ogre can't block.".to_string());
+        assert_eq!(compare(&gray_ogre(), &candidate), None);
+
+        let mut legendary = creature("Ogre King", "{2}{R}", 3.0, "3", "3");
+        legendary.type_line = Some("Legendary Creature — Ogre".to_string());
+        assert_eq!(compare(&gray_ogre(), &legendary), None);
+    }
+
+    #[test]
+    fn x_costs_compare_by_their_fixed_part() {
+        let target = spell(
+            "Heated Blast",
+            "{X}{R}{R}",
+            2.0,
+            "Heated Blast deals X damage to any target.",
+        );
+        let candidate = spell(
+            "Cheap Blast",
+            "{X}{R}",
+            1.0,
+            "Cheap Blast deals X damage to any target.",
+        );
+        assert_eq!(
+            compare(&target, &candidate),
+            Some(Comparison::StrictlyBetter(vec![
+                "lower mana value (1 vs 2)".to_string()
+            ]))
+        );
+        assert_eq!(compare(&candidate, &target), None);
+    }
+
+    #[test]
+    fn star_power_only_matches_itself() {
+        let target = creature("Star Ogre", "{2}{R}", 3.0, "*", "*");
+        let same = creature("Other Star Ogre", "{2}{R}", 3.0, "*", "*");
+        let fixed = creature("Fixed Ogre", "{2}{R}", 3.0, "5", "5");
+        assert_eq!(compare(&target, &same), Some(Comparison::FunctionalReprint));
+        assert_eq!(compare(&target, &fixed), None);
+        assert_eq!(compare(&fixed, &target), None);
+        // Variable stats with a fixed part are not parsed either
+        let plus = creature("Plus Ogre", "{2}{R}", 3.0, "1+*", "*");
+        assert_eq!(compare(&target, &plus), None);
+    }
+
+    fn with_text(mut card: CardModel, oracle_text: &str) -> CardModel {
+        card.oracle_text = Some(oracle_text.to_string());
+        card
+    }
+
+    #[test]
+    fn shared_phrases_match_normalized_self_references() {
+        let first = with_text(
+            creature("Gray Merchant of Asphodel", "{3}{B}{B}", 5.0, "2", "4"),
+            "When Gray Merchant of Asphodel enters, each opponent loses X life.",
+        );
+        let second = with_text(
+            creature("Isamaru, Hound of Konda", "{W}", 1.0, "2", "2"),
+            "When Isamaru enters, each opponent loses X life. (X is your devotion.)",
+        );
+        let third = with_text(
+            creature("Acolyte", "{2}{B}", 3.0, "1", "1"),
+            "When this creature enters, each opponent loses X life.",
+        );
+        let expected = vec!["when cardname enters each opponent loses x life".to_string()];
+        assert_eq!(shared_phrases(&first, &second), expected);
+        assert_eq!(shared_phrases(&first, &third), expected);
+    }
+
+    #[test]
+    fn overlapping_phrases_keep_only_the_longest() {
+        let first = spell(
+            "Careful Study",
+            "{U}",
+            1.0,
+            "Draw two cards, then discard two cards.",
+        );
+        let second = spell(
+            "Looting",
+            "{U}",
+            1.0,
+            "Draw two cards, then discard a card.\nWhenever you cycle a card, draw two cards.",
+        );
+        assert_eq!(
+            shared_phrases(&first, &second),
+            vec!["draw two cards then discard".to_string()]
+        );
+    }
+
+    #[test]
+    fn cards_without_shared_text_have_no_phrases() {
+        let first = spell("Shock", "{R}", 1.0, "Shock deals 2 damage to any target.");
+        let second = spell("Divination", "{2}{U}", 3.0, "Draw two cards.");
+        assert!(shared_phrases(&first, &second).is_empty());
+        // Two word overlaps are too short to count
+        let third = spell("Duress", "{B}", 1.0, "Target opponent reveals their hand.");
+        let fourth = spell("Peek", "{U}", 1.0, "Look at target opponent's hand.");
+        assert!(shared_phrases(&third, &fourth).is_empty());
+        assert!(shared_phrases(&gray_ogre(), &first).is_empty());
+    }
+
+    #[test]
+    fn explain_similarity_compares_types_keywords_colors_and_cost() {
+        let mut first = with_keywords(gray_ogre(), &["Trample", "Haste"]);
+        first.type_line = Some("Legendary Creature — Ogre Warrior".to_string());
+        let mut second = with_keywords(
+            creature("Ogre Shaman", "{3}{R}{G}", 5.0, "4", "4"),
+            &["trample", "Reach"],
+        );
+        second.type_line = Some("Creature — Ogre Shaman".to_string());
+        second.colors = Some(vec!["R".to_string(), "G".to_string()]);
+
+        let explanation = explain_similarity(&first, &second);
+        assert_eq!(explanation.embedding_similarity, None);
+        assert_eq!(explanation.type_line.shared, vec!["Creature", "Ogre"]);
+        assert_eq!(
+            explanation.type_line.only_first,
+            vec!["Legendary", "Warrior"]
+        );
+        assert_eq!(explanation.type_line.only_second, vec!["Shaman"]);
+        assert_eq!(explanation.keywords.shared, vec!["Trample"]);
+        assert_eq!(explanation.keywords.only_first, vec!["Haste"]);
+        assert_eq!(explanation.keywords.only_second, vec!["Reach"]);
+        assert_eq!(explanation.colors.shared, vec!["R"]);
+        assert_eq!(explanation.colors.only_second, vec!["G"]);
+        assert_eq!(explanation.cmc_delta, 2.0);
+
+        first.embedding = Some(Vector::from(vec![1.0, 0.0]));
+        second.embedding = Some(Vector::from(vec![1.0, 1.0]));
+        let similarity = explain_similarity(&first, &second)
+            .embedding_similarity
+            .unwrap();
+        assert!((similarity - std::f64::consts::FRAC_1_SQRT_2).abs() < 1e-6);
+    }
+}
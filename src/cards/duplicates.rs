@@ -0,0 +1,178 @@
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// Two cards whose embeddings are at least as similar as the detection threshold
+#[derive(Debug, Clone, Serialize, schemars::JsonSchema)]
+pub struct SimilarPair {
+    pub first_id: i32,
+    pub first_name: String,
+    pub second_id: i32,
+    pub second_name: String,
+    /// Cosine similarity of the two embeddings
+    pub similarity: f64,
+}
+
+#[derive(Debug, Clone, Serialize, schemars::JsonSchema)]
+pub struct FamilyMember {
+    pub id: i32,
+    pub name: String,
+}
+
+/// Cards connected by near-duplicate pairs, i.e. functional variants of each
+/// other
+#[derive(Debug, Clone, Serialize, schemars::JsonSchema)]
+pub struct CardFamily {
+    /// Members ordered by name
+    pub cards: Vec<FamilyMember>,
+    /// Lowest similarity among the pairs linking the family together
+    pub min_similarity: f64,
+    /// The near-duplicate pairs within the family, most similar first
+    pub pairs: Vec<SimilarPair>,
+}
+
+/// Result of a near-duplicate scan over the card pool
+#[derive(Debug, Clone, Serialize, schemars::JsonSchema)]
+pub struct DuplicateReport {
+    /// Minimum cosine similarity for two cards to count as near-duplicates
+    pub threshold: f64,
+    /// Nearest neighbors checked per card
+    pub max_neighbors: i64,
+    /// Number of near-duplicate pairs found
+    pub pair_count: usize,
+    /// Families of near-duplicates, largest first
+    pub families: Vec<CardFamily>,
+}
+
+/// Disjoint-set forest over card IDs
+#[derive(Default)]
+struct UnionFind {
+    parents: HashMap<i32, i32>,
+}
+
+impl UnionFind {
+    fn find(&mut self, id: i32) -> i32 {
+        let parent = *self.parents.entry(id).or_insert(id);
+        if parent == id {
+            return id;
+        }
+        let root = self.find(parent);
+        self.parents.insert(id, root);
+        root
+    }
+
+    fn union(&mut self, a: i32, b: i32) {
+        let (a, b) = (self.find(a), self.find(b));
+        if a != b {
+            self.parents.insert(a.max(b), a.min(b));
+        }
+    }
+}
+
+/// Groups near-duplicate pairs into families: connected components of the
+/// graph whose edges are the pairs
+pub fn group_families(pairs: Vec<SimilarPair>) -> Vec<CardFamily> {
+    let mut sets = UnionFind::default();
+    for pair in &pairs {
+        sets.union(pair.first_id, pair.second_id);
+    }
+
+    let mut families: HashMap<i32, CardFamily> = HashMap::new();
+    for pair in pairs {
+        let root = sets.find(pair.first_id);
+        let family = families.entry(root).or_insert_with(|| CardFamily {
+            cards: Vec::new(),
+            min_similarity: pair.similarity,
+            pairs: Vec::new(),
+        });
+        for (id, name) in [
+            (pair.first_id, &pair.first_name),
+            (pair.second_id, &pair.second_name),
+        ] {
+            if !family.cards.iter().any(|member| member.id == id) {
+                family.cards.push(FamilyMember {
+                    id,
+                    name: name.clone(),
+                });
+            }
+        }
+        family.min_similarity = family.min_similarity.min(pair.similarity);
+        family.pairs.push(pair);
+    }
+
+    let mut families: Vec<CardFamily> = families
+        .into_values()
+        .map(|mut family| {
+            family.cards.sort_by(|a, b| a.name.cmp(&b.name));
+            family
+                .pairs
+                .sort_by(|a, b| b.similarity.total_cmp(&a.similarity));
+            family
+        })
+        .collect();
+    families.sort_by(|a, b| {
+        b.cards
+            .len()
+            .cmp(&a.cards.len())
+            .then_with(|| a.cards[0].name.cmp(&b.cards[0].name))
+    });
+    families
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pair(first: (i32, &str), second: (i32, &str), similarity: f64) -> SimilarPair {
+        SimilarPair {
+            first_id: first.0,
+            first_name: first.1.to_string(),
+            second_id: second.0,
+            second_name: second.1.to_string(),
+            similarity,
+        }
+    }
+
+    fn names(family: &CardFamily) -> Vec<&str> {
+        family.cards.iter().map(|card| card.name.as_str()).collect()
+    }
+
+    #[test]
+    fn pairs_sharing_a_card_merge_transitively() {
+        let families = group_families(vec![
+            pair((1, "Gray Ogre"), (2, "Hill Giant"), 0.97),
+            pair((3, "Onyx Goblet"), (4, "Guttersnipe"), 0.95),
+            pair((2, "Hill Giant"), (5, "Bloodrock Cyclops"), 0.93),
+            pair((5, "Bloodrock Cyclops"), (6, "Cheap Ogre"), 0.99),
+        ]);
+
+        assert_eq!(families.len(), 2);
+        assert_eq!(
+            names(&families[0]),
+            vec!["Bloodrock Cyclops", "Cheap Ogre", "Gray Ogre", "Hill Giant"]
+        );
+        assert_eq!(families[0].min_similarity, 0.93);
+        let similarities: Vec<f64> = families[0].pairs.iter().map(|p| p.similarity).collect();
+        assert_eq!(similarities, vec![0.99, 0.97, 0.93]);
+        assert_eq!(names(&families[1]), vec!["Guttersnipe", "Onyx Goblet"]);
+    }
+
+    #[test]
+    fn cards_without_pairs_form_no_family() {
+        assert!(group_families(Vec::new()).is_empty());
+
+        let families = group_families(vec![pair((1, "Shock"), (2, "Burst"), 0.96)]);
+        assert_eq!(families.len(), 1);
+        assert_eq!(families[0].cards.len(), 2);
+        assert_eq!(families[0].pairs.len(), 1);
+    }
+
+    #[test]
+    fn negative_ids_are_grouped_like_any_other() {
+        let families = group_families(vec![
+            pair((-5, "Token A"), (0, "Token B"), 0.9),
+            pair((0, "Token B"), (i32::MIN, "Token C"), 0.9),
+        ]);
+        assert_eq!(families.len(), 1);
+        assert_eq!(names(&families[0]), vec!["Token A", "Token B", "Token C"]);
+    }
+}
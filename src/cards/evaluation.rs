@@ -0,0 +1,320 @@
+use crate::error::Error;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::path::Path;
+
+/// One entry of a golden file: a card and cards a good similarity search
+/// should return for it
+#[derive(Debug, Clone, Deserialize)]
+pub struct GoldenEntry {
+    pub card: String,
+    pub similar: Vec<String>,
+}
+
+/// Expected similar cards per query card, loaded from a golden file
+#[derive(Debug, Clone, Default)]
+pub struct GoldenSet {
+    /// Relevant card names per query card, in file order of first appearance
+    pub queries: Vec<(String, Vec<String>)>,
+}
+
+impl GoldenSet {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let path = path.as_ref();
+        let contents = std::fs::read_to_string(path).map_err(|e| {
+            tracing::error!("Failed to read golden file {:?}: {:?}", path, e);
+            Error::BadRequest(format!("Could not read golden file {}", path.display()))
+        })?;
+        Self::parse(&contents)
+    }
+
+    /// Parses a JSON array of `{"card": ..., "similar": [...]}` entries.
+    /// Entries for the same card (compared case-insensitively) are merged, so
+    /// a file may also list one expected pair per entry.
+    pub fn parse(contents: &str) -> Result<Self, Error> {
+        let entries: Vec<GoldenEntry> = serde_json::from_str(contents)
+            .map_err(|e| Error::BadRequest(format!("Invalid golden file: {}", e)))?;
+
+        let mut queries: Vec<(String, Vec<String>)> = Vec::new();
+        for entry in entries {
+            let position = queries
+                .iter()
+                .position(|(card, _)| card.eq_ignore_ascii_case(&entry.card));
+            let relevant = match position {
+                Some(index) => &mut queries[index].1,
+                None => {
+                    queries.push((entry.card, Vec::new()));
+                    &mut queries.last_mut().expect("just pushed").1
+                }
+            };
+            for name in entry.similar {
+                if !relevant.iter().any(|r| r.eq_ignore_ascii_case(&name)) {
+                    relevant.push(name);
+                }
+            }
+        }
+        queries.retain(|(_, relevant)| !relevant.is_empty());
+
+        if queries.is_empty() {
+            return Err(Error::BadRequest(
+                "Golden file contains no expected similar cards".to_string(),
+            ));
+        }
+        Ok(Self { queries })
+    }
+}
+
+/// Ranking quality of the results for one query card
+#[derive(Debug, Clone, Serialize)]
+pub struct QueryEvaluation {
+    pub card: String,
+    /// Fraction of the expected cards found in the top k
+    pub recall: f64,
+    /// Reciprocal rank of the first expected card in the top k, 0 if none
+    pub reciprocal_rank: f64,
+    /// Normalized discounted cumulative gain of the top k
+    pub ndcg: f64,
+    /// Expected cards missing from the top k
+    pub missed: Vec<String>,
+}
+
+/// Mean ranking quality over a golden set
+#[derive(Debug, Clone, Serialize)]
+pub struct EvaluationReport {
+    pub k: usize,
+    /// Queries that were evaluated
+    pub query_count: usize,
+    /// Mean recall@k
+    pub recall: f64,
+    /// Mean reciprocal rank
+    pub mrr: f64,
+    /// Mean nDCG@k
+    pub ndcg: f64,
+    pub queries: Vec<QueryEvaluation>,
+    /// Query cards that could not be searched, with the reason
+    pub skipped: BTreeMap<String, String>,
+}
+
+impl EvaluationReport {
+    pub fn new(k: usize, queries: Vec<QueryEvaluation>, skipped: BTreeMap<String, String>) -> Self {
+        let count = queries.len();
+        let mean = |metric: fn(&QueryEvaluation) -> f64| {
+            if count == 0 {
+                0.0
+            } else {
+                queries.iter().map(metric).sum::<f64>() / count as f64
+            }
+        };
+        Self {
+            k,
+            query_count: count,
+            recall: mean(|q| q.recall),
+            mrr: mean(|q| q.reciprocal_rank),
+            ndcg: mean(|q| q.ndcg),
+            queries,
+            skipped,
+        }
+    }
+}
+
+/// Scores a ranked result list against the expected cards with binary
+/// relevance; only the first `k` results count. Names are compared
+/// case-insensitively.
+pub fn evaluate_ranking(
+    card: &str,
+    ranked: &[String],
+    relevant: &[String],
+    k: usize,
+) -> QueryEvaluation {
+    let relevant_set: HashSet<String> = relevant.iter().map(|name| name.to_lowercase()).collect();
+    // A repeated name only counts the first time it is ranked
+    let mut found = HashSet::new();
+    let hits: Vec<bool> = ranked
+        .iter()
+        .take(k)
+        .map(|name| {
+            let name = name.to_lowercase();
+            relevant_set.contains(&name) && found.insert(name)
+        })
+        .collect();
+    let missed: Vec<String> = relevant
+        .iter()
+        .filter(|name| !found.contains(&name.to_lowercase()))
+        .cloned()
+        .collect();
+
+    let hit_count = hits.iter().filter(|&&hit| hit).count();
+    let recall = if relevant_set.is_empty() {
+        0.0
+    } else {
+        hit_count as f64 / relevant_set.len() as f64
+    };
+    let reciprocal_rank = hits
+        .iter()
+        .position(|&hit| hit)
+        .map(|index| 1.0 / (index + 1) as f64)
+        .unwrap_or(0.0);
+
+    let discount = |index: usize| 1.0 / ((index + 2) as f64).log2();
+    let dcg: f64 = hits
+        .iter()
+        .enumerate()
+        .filter(|(_, &hit)| hit)
+        .map(|(index, _)| discount(index))
+        .sum();
+    let ideal: f64 = (0..relevant_set.len().min(k)).map(discount).sum();
+    let ndcg = if ideal > 0.0 { dcg / ideal } else { 0.0 };
+
+    QueryEvaluation {
+        card: card.to_string(),
+        recall,
+        reciprocal_rank,
+        ndcg,
+        missed,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!(
+            (actual - expected).abs() < 1e-9,
+            "expected {expected}, got {actual}"
+        );
+    }
+
+    #[test]
+    fn hit_at_rank_one() {
+        let evaluation = evaluate_ranking(
+            "Shock",
+            &names(&["Lightning Bolt", "Opt", "Ponder"]),
+            &names(&["Lightning Bolt"]),
+            3,
+        );
+        assert_close(evaluation.recall, 1.0);
+        assert_close(evaluation.reciprocal_rank, 1.0);
+        assert_close(evaluation.ndcg, 1.0);
+        assert!(evaluation.missed.is_empty());
+    }
+
+    #[test]
+    fn hit_at_rank_three() {
+        let evaluation = evaluate_ranking(
+            "Shock",
+            &names(&["Opt", "Ponder", "Lightning Bolt"]),
+            &names(&["Lightning Bolt"]),
+            3,
+        );
+        assert_close(evaluation.recall, 1.0);
+        assert_close(evaluation.reciprocal_rank, 1.0 / 3.0);
+        // DCG 1 / log2(4) over an ideal DCG of 1 / log2(2)
+        assert_close(evaluation.ndcg, 0.5);
+    }
+
+    #[test]
+    fn no_hits() {
+        let evaluation = evaluate_ranking(
+            "Shock",
+            &names(&["Opt", "Ponder"]),
+            &names(&["Lightning Bolt"]),
+            2,
+        );
+        assert_close(evaluation.recall, 0.0);
+        assert_close(evaluation.reciprocal_rank, 0.0);
+        assert_close(evaluation.ndcg, 0.0);
+        assert_eq!(evaluation.missed, names(&["Lightning Bolt"]));
+    }
+
+    #[test]
+    fn hits_past_k_do_not_count() {
+        let evaluation = evaluate_ranking(
+            "Shock",
+            &names(&["Opt", "Lightning Bolt"]),
+            &names(&["Lightning Bolt"]),
+            1,
+        );
+        assert_close(evaluation.recall, 0.0);
+        assert_close(evaluation.reciprocal_rank, 0.0);
+        assert_close(evaluation.ndcg, 0.0);
+    }
+
+    #[test]
+    fn duplicate_names_count_once() {
+        let evaluation = evaluate_ranking(
+            "Shock",
+            &names(&["Lightning Bolt", "lightning bolt", "Chain Lightning"]),
+            &names(&["Lightning Bolt", "Chain Lightning"]),
+            3,
+        );
+        assert_close(evaluation.recall, 1.0);
+        assert_close(evaluation.reciprocal_rank, 1.0);
+        // DCG 1 + 1 / log2(4) over an ideal DCG of 1 + 1 / log2(3)
+        assert_close(evaluation.ndcg, 1.5 / (1.0 + 1.0 / 3f64.log2()));
+    }
+
+    #[test]
+    fn more_relevant_cards_than_k() {
+        let evaluation = evaluate_ranking(
+            "Shock",
+            &names(&["Lightning Bolt", "Chain Lightning"]),
+            &names(&[
+                "Lightning Bolt",
+                "Chain Lightning",
+                "Burst Lightning",
+                "Firebolt",
+            ]),
+            2,
+        );
+        assert_close(evaluation.recall, 0.5);
+        assert_close(evaluation.reciprocal_rank, 1.0);
+        // The ideal ranking also only has room for two relevant cards
+        assert_close(evaluation.ndcg, 1.0);
+        assert_eq!(evaluation.missed, names(&["Burst Lightning", "Firebolt"]));
+    }
+
+    #[test]
+    fn report_averages_queries() {
+        let report = EvaluationReport::new(
+            3,
+            vec![
+                evaluate_ranking(
+                    "Shock",
+                    &names(&["Lightning Bolt"]),
+                    &names(&["Lightning Bolt"]),
+                    3,
+                ),
+                evaluate_ranking("Opt", &names(&["Ponder"]), &names(&["Preordain"]), 3),
+            ],
+            BTreeMap::new(),
+        );
+        assert_eq!(report.query_count, 2);
+        assert_close(report.recall, 0.5);
+        assert_close(report.mrr, 0.5);
+        assert_close(report.ndcg, 0.5);
+    }
+
+    #[test]
+    fn golden_entries_for_the_same_card_are_merged() {
+        let golden = GoldenSet::parse(
+            r#"[
+                {"card": "Shock", "similar": ["Lightning Bolt"]},
+                {"card": "shock", "similar": ["lightning bolt", "Chain Lightning"]},
+                {"card": "Opt", "similar": []}
+            ]"#,
+        )
+        .unwrap();
+        assert_eq!(
+            golden.queries,
+            vec![(
+                "Shock".to_string(),
+                names(&["Lightning Bolt", "Chain Lightning"])
+            )]
+        );
+    }
+}
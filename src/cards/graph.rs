@@ -0,0 +1,319 @@
+use super::model::CardModel;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::str::FromStr;
+
+/// File format of an exported kNN graph
+#[derive(
+    Debug, Clone, Copy, Default, Deserialize, Serialize, PartialEq, Eq, schemars::JsonSchema,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum GraphFormat {
+    /// GraphML XML, readable by Gephi, Cytoscape, yEd and networkx
+    Graphml,
+    /// JSON adjacency list: nodes, each with its outgoing edges
+    #[default]
+    Json,
+    /// CSV edge list with a `source,target,similarity` header
+    Csv,
+}
+
+impl GraphFormat {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            GraphFormat::Graphml => "graphml",
+            GraphFormat::Json => "json",
+            GraphFormat::Csv => "csv",
+        }
+    }
+
+    pub fn mime_type(&self) -> &'static str {
+        match self {
+            GraphFormat::Graphml => "application/graphml+xml",
+            GraphFormat::Json => "application/json",
+            GraphFormat::Csv => "text/csv",
+        }
+    }
+}
+
+impl FromStr for GraphFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "graphml" => Ok(GraphFormat::Graphml),
+            "json" => Ok(GraphFormat::Json),
+            "csv" => Ok(GraphFormat::Csv),
+            other => Err(format!(
+                "Unknown graph format '{}' (expected graphml, json or csv)",
+                other
+            )),
+        }
+    }
+}
+
+/// Directed edge from a card to one of its nearest neighbors
+#[derive(Debug, Clone, Serialize)]
+pub struct KnnEdge {
+    pub source: i32,
+    pub target: i32,
+    pub similarity: f64,
+}
+
+/// A card in the graph
+#[derive(Debug, Clone, Serialize)]
+pub struct KnnNode {
+    pub id: i32,
+    pub name: String,
+    pub main_type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub type_line: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cmc: Option<f64>,
+    pub color_identity: Vec<String>,
+}
+
+impl From<&CardModel> for KnnNode {
+    fn from(card: &CardModel) -> Self {
+        Self {
+            id: card.id,
+            name: card.name.clone(),
+            main_type: card.main_type.as_str().to_string(),
+            type_line: card.type_line.clone(),
+            cmc: card.cmc,
+            color_identity: card.color_identity.clone().unwrap_or_default(),
+        }
+    }
+}
+
+/// k-nearest-neighbor graph over card embeddings: every card has an edge to
+/// each of its `k` nearest neighbors among the graph's cards
+#[derive(Debug, Clone, Serialize)]
+pub struct KnnGraph {
+    pub k: i64,
+    /// Distance metric the neighbors were ranked by
+    pub metric: String,
+    pub nodes: Vec<KnnNode>,
+    /// Edges grouped by source, nearest neighbor first
+    pub edges: Vec<KnnEdge>,
+}
+
+#[derive(Serialize)]
+struct AdjacencyNode<'a> {
+    #[serde(flatten)]
+    node: &'a KnnNode,
+    neighbors: Vec<AdjacencyEdge>,
+}
+
+#[derive(Serialize)]
+struct AdjacencyEdge {
+    id: i32,
+    similarity: f64,
+}
+
+#[derive(Serialize)]
+struct Adjacency<'a> {
+    k: i64,
+    metric: &'a str,
+    directed: bool,
+    nodes: Vec<AdjacencyNode<'a>>,
+}
+
+impl KnnGraph {
+    /// Serializes the graph in the given format
+    pub fn render(&self, format: GraphFormat) -> String {
+        match format {
+            GraphFormat::Graphml => self.to_graphml(),
+            GraphFormat::Json => self.to_json(),
+            GraphFormat::Csv => self.to_csv(),
+        }
+    }
+
+    fn to_json(&self) -> String {
+        let mut neighbors: HashMap<i32, Vec<AdjacencyEdge>> = HashMap::new();
+        for edge in &self.edges {
+            neighbors
+                .entry(edge.source)
+                .or_default()
+                .push(AdjacencyEdge {
+                    id: edge.target,
+                    similarity: edge.similarity,
+                });
+        }
+        let nodes = self
+            .nodes
+            .iter()
+            .map(|node| AdjacencyNode {
+                node,
+                neighbors: neighbors.remove(&node.id).unwrap_or_default(),
+            })
+            .collect();
+        let adjacency = Adjacency {
+            k: self.k,
+            metric: &self.metric,
+            directed: true,
+            nodes,
+        };
+        serde_json::to_string_pretty(&adjacency).unwrap_or_default()
+    }
+
+    fn to_csv(&self) -> String {
+        let mut csv = String::from("source,target,similarity\n");
+        for edge in &self.edges {
+            let _ = writeln!(csv, "{},{},{}", edge.source, edge.target, edge.similarity);
+        }
+        csv
+    }
+
+    fn to_graphml(&self) -> String {
+        let mut xml = String::from(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<graphml xmlns="http://graphml.graphdrawing.org/xmlns">
+  <key id="name" for="node" attr.name="name" attr.type="string"/>
+  <key id="main_type" for="node" attr.name="main_type" attr.type="string"/>
+  <key id="type_line" for="node" attr.name="type_line" attr.type="string"/>
+  <key id="cmc" for="node" attr.name="cmc" attr.type="double"/>
+  <key id="color_identity" for="node" attr.name="color_identity" attr.type="string"/>
+  <key id="similarity" for="edge" attr.name="similarity" attr.type="double"/>
+"#,
+        );
+        let _ = writeln!(xml, "  <graph id=\"knn\" edgedefault=\"directed\">");
+        for node in &self.nodes {
+            let _ = writeln!(xml, "    <node id=\"n{}\">", node.id);
+            let _ = writeln!(
+                xml,
+                "      <data key=\"name\">{}</data>",
+                escape_xml(&node.name)
+            );
+            let _ = writeln!(
+                xml,
+                "      <data key=\"main_type\">{}</data>",
+                escape_xml(&node.main_type)
+            );
+            if let Some(type_line) = &node.type_line {
+                let _ = writeln!(
+                    xml,
+                    "      <data key=\"type_line\">{}</data>",
+                    escape_xml(type_line)
+                );
+            }
+            if let Some(cmc) = node.cmc {
+                let _ = writeln!(xml, "      <data key=\"cmc\">{}</data>", cmc);
+            }
+            let _ = writeln!(
+                xml,
+                "      <data key=\"color_identity\">{}</data>",
+                node.color_identity.join("")
+            );
+            xml.push_str("    </node>\n");
+        }
+        for edge in &self.edges {
+            let _ = writeln!(
+                xml,
+                "    <edge source=\"n{}\" target=\"n{}\"><data key=\"similarity\">{}</data></edge>",
+                edge.source, edge.target, edge.similarity
+            );
+        }
+        xml.push_str("  </graph>\n</graphml>\n");
+        xml
+    }
+}
+
+fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node(id: i32, name: &str) -> KnnNode {
+        KnnNode {
+            id,
+            name: name.to_string(),
+            main_type: "Creature".to_string(),
+            type_line: Some("Creature — Ogre".to_string()),
+            cmc: Some(3.0),
+            color_identity: vec!["R".to_string(), "G".to_string()],
+        }
+    }
+
+    fn graph() -> KnnGraph {
+        KnnGraph {
+            k: 1,
+            metric: "cosine".to_string(),
+            nodes: vec![node(1, "Dungeons & <Dragons>"), node(2, "Urza's \"Tower\"")],
+            edges: vec![
+                KnnEdge {
+                    source: 1,
+                    target: 2,
+                    similarity: 0.5,
+                },
+                KnnEdge {
+                    source: 2,
+                    target: 1,
+                    similarity: 0.25,
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn escapes_xml_special_characters() {
+        assert_eq!(
+            escape_xml(r#"Dungeons & <Dragons> "Urza's""#),
+            "Dungeons &amp; &lt;Dragons&gt; &quot;Urza&apos;s&quot;"
+        );
+        assert_eq!(escape_xml("Gray Ogre"), "Gray Ogre");
+    }
+
+    #[test]
+    fn graphml_escapes_card_names() {
+        let xml = graph().render(GraphFormat::Graphml);
+        assert!(xml.contains("<data key=\"name\">Dungeons &amp; &lt;Dragons&gt;</data>"));
+        assert!(xml.contains("<data key=\"name\">Urza&apos;s &quot;Tower&quot;</data>"));
+        assert!(!xml.contains("<Dragons>"));
+        assert!(xml.contains("<data key=\"color_identity\">RG</data>"));
+        assert!(xml.contains(
+            "<edge source=\"n1\" target=\"n2\"><data key=\"similarity\">0.5</data></edge>"
+        ));
+        assert!(xml.ends_with("</graph>\n</graphml>\n"));
+    }
+
+    #[test]
+    fn csv_lists_one_edge_per_line_after_a_header() {
+        assert_eq!(
+            graph().render(GraphFormat::Csv),
+            "source,target,similarity\n1,2,0.5\n2,1,0.25\n"
+        );
+        let empty = KnnGraph {
+            edges: Vec::new(),
+            ..graph()
+        };
+        assert_eq!(empty.render(GraphFormat::Csv), "source,target,similarity\n");
+    }
+
+    #[test]
+    fn json_nests_edges_under_their_source() {
+        let json: serde_json::Value =
+            serde_json::from_str(&graph().render(GraphFormat::Json)).unwrap();
+        assert_eq!(json["directed"], true);
+        assert_eq!(json["nodes"][0]["name"], "Dungeons & <Dragons>");
+        assert_eq!(
+            json["nodes"][0]["neighbors"],
+            serde_json::json!([{ "id": 2, "similarity": 0.5 }])
+        );
+    }
+}
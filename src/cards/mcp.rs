@@ -1,3 +1,7 @@
+use super::backfill::{BackfillMode, BackfillProgress};
+use super::comparison::SimilarityExplanation;
+use super::graph::GraphFormat;
+use super::model::{CardResponse, DistanceMetric, EmbeddingSpace, VectorIndex};
 use schemars;
 
 #[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
@@ -13,15 +17,21 @@ pub struct SearchCardsRequest {
     /// Number of results to skip for pagination
     #[serde(skip_serializing_if = "Option::is_none")]
     pub offset: Option<i32>,
+    /// Card fields to include for each result (default: name)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response_fields: Option<Vec<CardField>>,
 }
 
 #[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
 pub struct GetCardByIdRequest {
     /// Card ID to retrieve
     pub id: i32,
+    /// Card fields to include in the response (default: name, id, main_type)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response_fields: Option<Vec<CardField>>,
 }
 
-#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+#[derive(Debug, Default, serde::Deserialize, schemars::JsonSchema)]
 pub struct SearchFilters {
     /// Card type to filter by - options are: Artifact, Battle, Conspiracy, Creature, Dungeon, Enchantment, Instant, Kindred, Land, Phenomenon, Plane, Planeswalker, Scheme, Sorcery, Vanguard, Unknown
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -29,13 +39,672 @@ pub struct SearchFilters {
     /// Fields to search across when a query is provided - options are: name, type
     #[serde(skip_serializing_if = "Option::is_none")]
     pub fields: Option<Vec<String>>,
+    /// Minimum number of mana symbols of each color in the mana cost, e.g. { "black": 3 } for devotion to black of at least 3. Hybrid symbols count toward each of their colors
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_pips: Option<PipFilter>,
+    /// Only return permanent cards (artifacts, battles, creatures, enchantments, lands, planeswalkers)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub permanents_only: Option<bool>,
+    /// Restrict the search to cards with these names, e.g. the cards of a decklist (case-insensitive; a front face name matches a double-faced card)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub card_names: Option<Vec<String>>,
+    /// Restrict the search to cards with these IDs. Combined with card_names, cards matching either list are searched
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub card_ids: Option<Vec<i32>>,
+    /// Only return cards legal (or restricted) in this format, e.g. standard, pioneer, modern, legacy, vintage, pauper, commander
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub legal_in: Option<String>,
+    /// Only return cards whose color identity is within these colors (W, U, B, R, G), e.g. ["U", "B"] for a Dimir deck
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub color_identity_within: Option<Vec<String>>,
+    /// Only return cards with at most this mana value
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_cmc: Option<f64>,
+    /// Exclude cards on the reserved list
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exclude_reserved: Option<bool>,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct PipFilter {
+    /// Minimum number of white pips
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub white: Option<u32>,
+    /// Minimum number of blue pips
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub blue: Option<u32>,
+    /// Minimum number of black pips
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub black: Option<u32>,
+    /// Minimum number of red pips
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub red: Option<u32>,
+    /// Minimum number of green pips
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub green: Option<u32>,
+    /// Minimum number of colorless ({C}) pips
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub colorless: Option<u32>,
 }
 
 #[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
 pub struct FindSimilarCardsRequest {
     /// Name of the card to find similar cards for
     pub card_name: String,
+    /// Further seed cards; results resemble all seeds, whose embeddings are averaged with card_name's
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub additional_card_names: Option<Vec<String>>,
+    /// Cards the results should not resemble, e.g. to steer away from a mechanic shared with the seeds
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub negative_card_names: Option<Vec<String>>,
+    /// How strongly to steer away from the negative cards, from 0 to 1 (default: 0.5)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub negative_weight: Option<f32>,
+    /// Diversify results with maximal marginal relevance, from 0 (pure similarity order) to 1 (maximally varied); e.g. 0.3 avoids lists of near-identical reprints (default: 0)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub diversity: Option<f32>,
+    /// Only return cards whose similarity to the seeds is at least this value (e.g. 0.8 with the cosine metric), so weak matches yield no results rather than poor ones
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_similarity: Option<f64>,
+    /// Named embedding space (model id) to search, as listed by list_embedding_spaces (default: the card table's embedding column)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub embedding_space: Option<String>,
+    /// Distance metric: cosine, l2 or inner_product (default: cosine). A matching vector index is needed to avoid a full scan
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metric: Option<DistanceMetric>,
+    /// HNSW search candidate list size for this query; higher improves recall at the cost of speed (pgvector default: 40)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ef_search: Option<u32>,
+    /// Number of IVFFlat lists to probe for this query; higher improves recall at the cost of speed (pgvector default: 1)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub probes: Option<u32>,
+    /// Filters constraining which cards can be returned (format legality, color identity, main type, max cmc, reserved list, ...)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filters: Option<SearchFilters>,
     /// Maximum number of similar cards to return (default: 10)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub limit: Option<i32>,
+    /// Card fields to include for each result (default: name, main_type, cmc, mana_cost, colors, keywords, power, toughness, oracle_text)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response_fields: Option<Vec<CardField>>,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct SemanticSearchRequest {
+    /// Natural-language description of the cards to find, e.g. "cheap artifact removal that exiles"
+    pub query: String,
+    /// Maximum number of cards to return (default: 10)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<i32>,
+    /// Named embedding space (model id) to search; must be the configured embedder's own space (default: the card table's embedding column)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub embedding_space: Option<String>,
+    /// Card fields to include for each result (default: name, main_type, cmc, mana_cost, colors, keywords, power, toughness, oracle_text)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response_fields: Option<Vec<CardField>>,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct HybridSearchRequest {
+    /// Search text, matched both as exact terms (e.g. "Treasure") and by meaning
+    pub query: String,
+    /// Maximum number of cards to return (default: 10)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<i32>,
+    /// Weight of the full-text ranking in the fused score (default: 1.0, 0 disables it)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lexical_weight: Option<f64>,
+    /// Weight of the vector similarity ranking in the fused score (default: 1.0, 0 disables it)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vector_weight: Option<f64>,
+    /// Reciprocal rank fusion constant k, greater than 0; higher values flatten the advantage of top ranks (default: 60)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rank_constant: Option<f64>,
+    /// Named embedding space (model id) to search; must be the configured embedder's own space (default: the card table's embedding column)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub embedding_space: Option<String>,
+    /// Card fields to include for each result (default: name, main_type, cmc, mana_cost, colors, keywords, power, toughness, oracle_text)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response_fields: Option<Vec<CardField>>,
+}
+
+#[derive(Debug, Clone, Copy, serde::Deserialize, schemars::JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum IndexMethod {
+    Hnsw,
+    Ivfflat,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct CreateVectorIndexRequest {
+    /// Index type: hnsw (better speed/recall, slower to build) or ivfflat (faster to build, build after loading embeddings)
+    pub method: IndexMethod,
+    /// Distance metric the index serves: cosine, l2 or inner_product (default: cosine)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metric: Option<DistanceMetric>,
+    /// HNSW: maximum connections per layer (default: 16)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub m: Option<u32>,
+    /// HNSW: candidate list size while building (default: 64)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ef_construction: Option<u32>,
+    /// IVFFlat: number of lists, e.g. rows / 1000 (default: 100)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lists: Option<u32>,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct DropVectorIndexRequest {
+    /// Name of the index, as returned by list_vector_indexes
+    pub name: String,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct SuggestReplacementsRequest {
+    /// Name of the card to replace, e.g. one that is banned, on the Reserved List or outside the deck's colors
+    pub card_name: String,
+    /// Format the replacements must be legal in, e.g. modern, pauper, commander
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub format: Option<String>,
+    /// The deck's color identity; replacements must fit within it, e.g. ["U", "B"]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub color_identity: Option<Vec<String>>,
+    /// Exclude Reserved List cards (default: false)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exclude_reserved: Option<bool>,
+    /// Only suggest cards of the same main type as the replaced card (default: true)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub same_card_type: Option<bool>,
+    /// Maximum number of replacements to return (default: 10)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<i32>,
+    /// Fields to include for each replacement (default: name, main_type, cmc, mana_cost, colors, keywords, power, toughness, oracle_text)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response_fields: Option<Vec<CardField>>,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct CompareCardsRequest {
+    /// Name of the first card
+    pub first_card_name: String,
+    /// Name of the second card
+    pub second_card_name: String,
+    /// Fields to include for both cards (default: name, main_type, cmc, mana_cost, colors, keywords, power, toughness, oracle_text)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response_fields: Option<Vec<CardField>>,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct ClusterCardsRequest {
+    /// Filters selecting the cards to cluster, e.g. { "legal_in": "pauper", "color_identity_within": ["U"] }
+    pub filters: SearchFilters,
+    /// Number of clusters (default: 8)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub clusters: Option<usize>,
+    /// Cards listed per cluster, closest to its center first (default: 5)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub representatives: Option<usize>,
+    /// Keywords listed per cluster (default: 5)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub keywords: Option<usize>,
+    /// Maximum number of cards to cluster, at most 20000 (default: 2000)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_cards: Option<i64>,
+    /// Random seed for cluster initialization; the same seed gives the same clusters (default: 0)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u64>,
+    /// Fields to include for each representative card (default: name, main_type, cmc, mana_cost, colors, keywords, power, toughness, oracle_text)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response_fields: Option<Vec<CardField>>,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct ProjectCardsRequest {
+    /// Filters selecting the cards to project, e.g. { "legal_in": "pauper" }
+    pub filters: SearchFilters,
+    /// Maximum number of cards to project, at most 20000 (default: 2000)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_cards: Option<i64>,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct ExportKnnGraphRequest {
+    /// Filters selecting the cards in the graph; all embedded cards when omitted
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filters: Option<SearchFilters>,
+    /// Nearest neighbors linked from each card, at most 50 (default: 10)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub k: Option<i64>,
+    /// Maximum number of cards in the graph, taken by ID, at most 5000 (default: 2000)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_cards: Option<i64>,
+    /// Distance metric: cosine, l2 or inner_product (default: cosine)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metric: Option<DistanceMetric>,
+    /// Output format: graphml, json (adjacency list) or csv (edge list) (default: json)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub format: Option<GraphFormat>,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct FindNearDuplicatesRequest {
+    /// Minimum cosine similarity for two cards to count as near-duplicates (default: 0.95)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub threshold: Option<f64>,
+    /// Nearest neighbors checked per card; pairs outside both cards' neighbors are missed (default: 10)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_neighbors: Option<i64>,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct BackfillEmbeddingsRequest {
+    /// Which cards to embed: missing (no embedding), stale (embedding of a different dimension than the embedder's) or all (default: missing)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mode: Option<BackfillMode>,
+    /// Cards embedded and written per transaction (default: 100)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub batch_size: Option<i64>,
+    /// Resume after this card ID, as returned in last_id by an earlier call
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub after_id: Option<i32>,
+    /// Stop after this many cards; call again with after_id to continue (default: 1000)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_cards: Option<i64>,
+    /// Store vectors in the embedder's named embedding space instead of the card table's embedding column (default: false)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub named_space: Option<bool>,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct FindStrictlyBetterCardsRequest {
+    /// Name of the card to find strictly better versions and functional reprints of
+    pub card_name: String,
+    /// Maximum number of cards to return in each category (default: 10)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<i32>,
+}
+
+/// A `CardResponse` field that can be selected for tool output
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Deserialize, schemars::JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CardField {
+    Id,
+    Name,
+    MainType,
+    TypeLine,
+    OracleText,
+    Keywords,
+    Cmc,
+    ManaCost,
+    Colors,
+    ColorIdentity,
+    Power,
+    Toughness,
+    Games,
+    Legalities,
+    Reserved,
+    GameChanger,
+    Pips,
+}
+
+impl CardField {
+    pub const SEARCH_DEFAULT: &'static [CardField] = &[CardField::Name];
+
+    pub const GET_DEFAULT: &'static [CardField] =
+        &[CardField::Name, CardField::Id, CardField::MainType];
+
+    pub const SIMILAR_DEFAULT: &'static [CardField] = &[
+        CardField::Name,
+        CardField::MainType,
+        CardField::Cmc,
+        CardField::ManaCost,
+        CardField::Colors,
+        CardField::Keywords,
+        CardField::Power,
+        CardField::Toughness,
+        CardField::OracleText,
+    ];
+
+    /// The requested fields, or `default` when none or an empty list was given
+    pub fn selected<'a>(
+        fields: Option<&'a [CardField]>,
+        default: &'a [CardField],
+    ) -> &'a [CardField] {
+        match fields {
+            Some(fields) if !fields.is_empty() => fields,
+            _ => default,
+        }
+    }
+
+    /// The `CardResponse` key this field is serialized under
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CardField::Id => "id",
+            CardField::Name => "name",
+            CardField::MainType => "main_type",
+            CardField::TypeLine => "type_line",
+            CardField::OracleText => "oracle_text",
+            CardField::Keywords => "keywords",
+            CardField::Cmc => "cmc",
+            CardField::ManaCost => "mana_cost",
+            CardField::Colors => "colors",
+            CardField::ColorIdentity => "color_identity",
+            CardField::Power => "power",
+            CardField::Toughness => "toughness",
+            CardField::Games => "games",
+            CardField::Legalities => "legalities",
+            CardField::Reserved => "reserved",
+            CardField::GameChanger => "game_changer",
+            CardField::Pips => "pips",
+        }
+    }
+
+    /// Renders this field of the card for text output, or `None` when the card
+    /// has no value for it
+    fn render(&self, card: &CardResponse) -> Option<String> {
+        let list = |values: &Option<Vec<String>>| {
+            values
+                .as_ref()
+                .filter(|values| !values.is_empty())
+                .map(|values| values.join(", "))
+        };
+
+        match self {
+            CardField::Id => Some(format!("ID: {}", card.id)),
+            CardField::Name => Some(card.name.clone()),
+            CardField::MainType => Some(format!("Type: {}", card.main_type)),
+            CardField::TypeLine => card.type_line.as_ref().map(|t| format!("Type Line: {}", t)),
+            CardField::OracleText => card
+                .oracle_text
+                .as_ref()
+                .filter(|text| !text.is_empty())
+                .map(|text| format!("Text: {}", text)),
+            CardField::Keywords => list(&card.keywords).map(|k| format!("Keywords: {}", k)),
+            CardField::Cmc => card.cmc.map(|cmc| format!("CMC: {}", cmc)),
+            CardField::ManaCost => card.mana_cost.as_ref().map(|c| format!("Cost: {}", c)),
+            CardField::Colors => list(&card.colors).map(|c| format!("Colors: {}", c)),
+            CardField::ColorIdentity => {
+                list(&card.color_identity).map(|c| format!("Color Identity: {}", c))
+            }
+            CardField::Power => card.power.as_ref().map(|p| format!("Power: {}", p)),
+            CardField::Toughness => card.toughness.as_ref().map(|t| format!("Toughness: {}", t)),
+            CardField::Games => list(&card.games).map(|g| format!("Games: {}", g)),
+            CardField::Legalities => card.legalities.as_ref().and_then(|legalities| {
+                let legal: Vec<&str> = legalities
+                    .as_object()?
+                    .iter()
+                    .filter(|(_, status)| status.as_str() == Some("legal"))
+                    .map(|(format, _)| format.as_str())
+                    .collect();
+                Some(format!("Legal in: {}", legal.join(", ")))
+            }),
+            CardField::Reserved => card.reserved.map(|r| format!("Reserved: {}", r)),
+            CardField::GameChanger => card.game_changer.map(|g| format!("Game Changer: {}", g)),
+            CardField::Pips => card.pips.map(|pips| {
+                let counts: Vec<String> = pips
+                    .by_symbol()
+                    .iter()
+                    .filter(|(_, count)| *count > 0)
+                    .map(|(symbol, count)| format!("{}={}", symbol, count))
+                    .collect();
+                format!("Pips: {}", counts.join(" "))
+            }),
+        }
+    }
+}
+
+/// Formats the selected fields of a card as a single ` | `-separated line.
+/// Name and main type are combined as `Name (Type)`, and power and toughness
+/// as `P/T`, when both are selected.
+pub fn format_card_fields(card: &CardResponse, fields: &[CardField]) -> String {
+    let has = |field: CardField| fields.contains(&field);
+    let mut details = Vec::new();
+
+    if has(CardField::Name) && has(CardField::MainType) {
+        details.push(format!("{} ({})", card.name, card.main_type));
+    }
+    for field in fields {
+        let combined = match field {
+            CardField::Name | CardField::MainType => {
+                has(CardField::Name) && has(CardField::MainType)
+            }
+            CardField::Power | CardField::Toughness => {
+                has(CardField::Power) && has(CardField::Toughness)
+            }
+            _ => false,
+        };
+        if !combined {
+            details.extend(field.render(card));
+        } else if *field == CardField::Toughness {
+            if let (Some(power), Some(toughness)) = (&card.power, &card.toughness) {
+                details.push(format!("{}/{}", power, toughness));
+            }
+        }
+    }
+
+    details.join(" | ")
+}
+
+/// Serializes the selected fields of a card for structured output. `id`,
+/// `name` and `main_type` are always included so results can be identified.
+pub fn project_card(card: &CardResponse, fields: &[CardField]) -> ProjectedCard {
+    let mut projected = match serde_json::to_value(card) {
+        Ok(serde_json::Value::Object(map)) => map,
+        _ => serde_json::Map::new(),
+    };
+    projected.retain(|key, _| {
+        matches!(key.as_str(), "id" | "name" | "main_type")
+            || fields.iter().any(|field| field.as_str() == key)
+    });
+    projected
+}
+
+/// A card serialized with only the requested `CardResponse` fields
+pub type ProjectedCard = serde_json::Map<String, serde_json::Value>;
+
+#[derive(Debug, serde::Serialize, schemars::JsonSchema)]
+pub struct SearchCardsResponse {
+    /// Number of cards returned
+    pub count: usize,
+    /// Number of results skipped
+    pub offset: i32,
+    /// Maximum number of results requested
+    pub limit: i32,
+    /// Matching cards, containing id, name, main_type and the requested response fields
+    #[schemars(with = "Vec<CardResponse>")]
+    pub cards: Vec<ProjectedCard>,
+}
+
+#[derive(Debug, serde::Serialize, schemars::JsonSchema)]
+pub struct CardCountResponse {
+    /// Total number of cards in the database
+    pub count: i64,
+}
+
+#[derive(Debug, serde::Serialize, schemars::JsonSchema)]
+pub struct SimilarCardsResponse {
+    /// Names of the seed cards similar cards were found for
+    pub card_names: Vec<String>,
+    /// Names of the cards results were steered away from
+    pub negative_card_names: Vec<String>,
+    /// Embedding space searched, if not the card table's embedding column
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub embedding_space: Option<String>,
+    /// Distance metric used
+    pub metric: DistanceMetric,
+    /// Minimum similarity applied, if any
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_similarity: Option<f64>,
+    /// Similar cards ordered from most to least similar
+    pub matches: Vec<SimilarCardMatch>,
+}
+
+#[derive(Debug, serde::Serialize, schemars::JsonSchema)]
+pub struct SimilarCardMatch {
+    /// The card, containing id, name, main_type and the requested response fields
+    #[schemars(with = "CardResponse")]
+    pub card: ProjectedCard,
+    /// Similarity to the seed cards under the chosen metric (cosine similarity by default)
+    pub similarity: f64,
+}
+
+#[derive(Debug, serde::Serialize, schemars::JsonSchema)]
+pub struct SemanticSearchResponse {
+    /// The natural-language query that was embedded
+    pub query: String,
+    /// Cards ordered from most to least similar to the query, containing id, name, main_type and the requested response fields
+    #[schemars(with = "Vec<CardResponse>")]
+    pub cards: Vec<ProjectedCard>,
+}
+
+#[derive(Debug, serde::Serialize, schemars::JsonSchema)]
+pub struct SuggestReplacementsResponse {
+    /// Name of the replaced card
+    pub card_name: String,
+    /// Why the card does not fit the given constraints; empty if it already does
+    pub reasons: Vec<String>,
+    /// Closest cards satisfying the constraints, most similar first
+    pub replacements: Vec<SimilarCardMatch>,
+}
+
+#[derive(Debug, serde::Serialize, schemars::JsonSchema)]
+pub struct CompareCardsResponse {
+    /// The first card, containing id, name, main_type and the requested response fields
+    #[schemars(with = "CardResponse")]
+    pub first: ProjectedCard,
+    /// The second card, containing id, name, main_type and the requested response fields
+    #[schemars(with = "CardResponse")]
+    pub second: ProjectedCard,
+    #[serde(flatten)]
+    pub explanation: SimilarityExplanation,
+}
+
+#[derive(Debug, serde::Serialize, schemars::JsonSchema)]
+pub struct KeywordCount {
+    pub keyword: String,
+    /// Number of cards in the cluster with the keyword
+    pub count: usize,
+}
+
+#[derive(Debug, serde::Serialize, schemars::JsonSchema)]
+pub struct CardClusterOutput {
+    /// Number of cards in the cluster
+    pub size: usize,
+    /// Mean cosine similarity of the cluster's cards to its center; higher means a tighter theme
+    pub cohesion: f64,
+    /// Most common keywords among the cluster's cards
+    pub keywords: Vec<KeywordCount>,
+    /// Cards closest to the cluster's center, containing id, name, main_type and the requested response fields
+    #[schemars(with = "Vec<CardResponse>")]
+    pub representatives: Vec<ProjectedCard>,
+}
+
+#[derive(Debug, serde::Serialize, schemars::JsonSchema)]
+pub struct ClusterCardsResponse {
+    /// Number of cards clustered
+    pub card_count: usize,
+    /// Clusters, largest first
+    pub clusters: Vec<CardClusterOutput>,
+}
+
+#[derive(Debug, serde::Serialize, schemars::JsonSchema)]
+pub struct CardPoint {
+    pub id: i32,
+    /// Label for the point
+    pub name: String,
+    pub main_type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub type_line: Option<String>,
+    pub color_identity: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cmc: Option<f64>,
+    /// Coordinate along the first principal component
+    pub x: f32,
+    /// Coordinate along the second principal component
+    pub y: f32,
+}
+
+#[derive(Debug, serde::Serialize, schemars::JsonSchema)]
+pub struct ProjectCardsResponse {
+    /// Number of cards projected
+    pub card_count: usize,
+    /// Fraction of the embeddings' variance captured by the x and y axes
+    pub explained_variance: [f32; 2],
+    pub points: Vec<CardPoint>,
+}
+
+#[derive(Debug, serde::Serialize, schemars::JsonSchema)]
+pub struct KnnGraphResponse {
+    pub format: GraphFormat,
+    /// MIME type of `content`
+    pub mime_type: String,
+    pub node_count: usize,
+    pub edge_count: usize,
+    /// The exported graph
+    pub content: String,
+}
+
+#[derive(Debug, serde::Serialize, schemars::JsonSchema)]
+pub struct BackfillEmbeddingsResponse {
+    /// Backfill mode that was run
+    pub mode: BackfillMode,
+    #[serde(flatten)]
+    pub progress: BackfillProgress,
+}
+
+#[derive(Debug, serde::Serialize, schemars::JsonSchema)]
+pub struct EmbeddingSpacesResponse {
+    /// Named embedding spaces, one per embedding model
+    pub spaces: Vec<EmbeddingSpace>,
+}
+
+#[derive(Debug, serde::Serialize, schemars::JsonSchema)]
+pub struct VectorIndexesResponse {
+    /// HNSW and IVFFlat indexes on card embeddings
+    pub indexes: Vec<VectorIndex>,
+}
+
+#[derive(Debug, serde::Serialize, schemars::JsonSchema)]
+pub struct VectorIndexResponse {
+    /// Name of the created or dropped index
+    pub name: String,
+}
+
+#[derive(Debug, serde::Serialize, schemars::JsonSchema)]
+pub struct HybridSearchMatch {
+    #[schemars(with = "CardResponse")]
+    pub card: ProjectedCard,
+    /// Weighted reciprocal rank fusion score
+    pub score: f64,
+    /// 1-based rank among full-text matches, if matched
+    pub lexical_rank: Option<usize>,
+    /// 1-based rank among vector neighbors, if matched
+    pub vector_rank: Option<usize>,
+}
+
+#[derive(Debug, serde::Serialize, schemars::JsonSchema)]
+pub struct HybridSearchResponse {
+    /// The search text
+    pub query: String,
+    /// Cards ordered by fused score, highest first
+    pub matches: Vec<HybridSearchMatch>,
+}
+
+#[derive(Debug, serde::Serialize, schemars::JsonSchema)]
+pub struct StrictlyBetterCard {
+    #[schemars(with = "CardResponse")]
+    pub card: ProjectedCard,
+    /// Ways in which the card is better
+    pub reasons: Vec<String>,
+}
+
+#[derive(Debug, serde::Serialize, schemars::JsonSchema)]
+pub struct StrictlyBetterCardsResponse {
+    /// Name of the card that was compared against
+    pub card_name: String,
+    /// Cards that are strictly better than the given card
+    pub strictly_better: Vec<StrictlyBetterCard>,
+    /// Cards that are functionally identical to the given card
+    #[schemars(with = "Vec<CardResponse>")]
+    pub functional_reprints: Vec<ProjectedCard>,
 }
@@ -1,7 +1,16 @@
+pub mod backfill;
+pub mod comparison;
+pub mod duplicates;
+pub mod evaluation;
+pub mod graph;
 pub mod mcp;
 mod model;
 pub mod repository;
 pub mod service;
 
-pub use model::CardModel;
+pub use model::{
+    CardCluster, CardModel, CardProjection, CardResponse, DistanceMetric, EmbeddingSpace,
+    HybridMatch, ManaPips, ReplacementConstraints, Replacements, SimilarCard, SimilarCardsQuery,
+    VectorIndex, VectorIndexMethod, VectorSearchOptions,
+};
 pub use repository::CardRepository;
@@ -1,9 +1,10 @@
+use crate::similarity::vector;
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 use std::fmt;
 use pgvector::Vector;
 
-#[derive(Debug, FromRow)]
+#[derive(Debug, Clone, FromRow)]
 pub struct CardModel {
     pub id: i32,
     pub name: String,
@@ -24,8 +25,243 @@ pub struct CardModel {
     pub embedding: Option<Vector>,
 }
 
+/// Parameters of a similar-card search seeded by one or more cards
+#[derive(Debug, Clone)]
+pub struct SimilarCardsQuery {
+    /// Cards the results should resemble; their embeddings are averaged
+    pub card_names: Vec<String>,
+    /// Cards the results should not resemble
+    pub negative_card_names: Vec<String>,
+    /// How strongly to steer away from the negative cards
+    pub negative_weight: f32,
+    /// Maximal marginal relevance trade-off between relevance (0) and
+    /// covering different variants (1)
+    pub diversity: f32,
+    /// Distance metric and ANN index tuning for the vector query
+    pub vector_options: VectorSearchOptions,
+    /// Neighbors less similar than this to the query vector are dropped
+    pub min_similarity: Option<f64>,
+    pub limit: i64,
+}
+
+impl Default for SimilarCardsQuery {
+    fn default() -> Self {
+        Self {
+            card_names: Vec::new(),
+            negative_card_names: Vec::new(),
+            negative_weight: 0.5,
+            diversity: 0.0,
+            vector_options: VectorSearchOptions::default(),
+            min_similarity: None,
+            limit: 10,
+        }
+    }
+}
+
+/// A card found by embedding distance, with its similarity to the query vector
+/// under the metric used (see [`DistanceMetric::similarity_sql`])
+#[derive(Debug)]
+pub struct SimilarCard {
+    pub card: CardModel,
+    pub similarity: f64,
+}
+
+/// Constraints a replacement card has to satisfy
+#[derive(Debug, Clone, Default)]
+pub struct ReplacementConstraints {
+    /// Format the replacement must be legal (or restricted) in
+    pub format: Option<String>,
+    /// Color identity the replacement must fit within
+    pub color_identity: Option<Vec<String>>,
+    pub exclude_reserved: bool,
+    /// Only consider cards of the replaced card's main type
+    pub same_card_type: bool,
+}
+
+/// Legal alternatives to a card that does not fit a deck
+#[derive(Debug)]
+pub struct Replacements {
+    pub card: CardModel,
+    /// Constraints the card itself violates
+    pub reasons: Vec<String>,
+    pub suggestions: Vec<SimilarCard>,
+}
+
+/// A group of cards whose embeddings cluster together
+#[derive(Debug)]
+pub struct CardCluster {
+    /// Cards in the cluster, closest to the centroid first
+    pub cards: Vec<SimilarCard>,
+    /// Keywords shared by the cluster's cards with how many have each, most
+    /// common first
+    pub keywords: Vec<(String, usize)>,
+}
+
+/// Cards laid out in 2D by the first two principal components of their
+/// embeddings
+#[derive(Debug)]
+pub struct CardProjection {
+    /// Each card with its `[x, y]` coordinates
+    pub cards: Vec<(CardModel, [f32; 2])>,
+    /// Fraction of the embeddings' variance captured by each axis
+    pub explained_variance: [f32; 2],
+}
+
+/// Distance metrics supported by pgvector
+#[derive(
+    Debug, Clone, Copy, Default, Deserialize, Serialize, PartialEq, Eq, schemars::JsonSchema,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum DistanceMetric {
+    #[default]
+    Cosine,
+    L2,
+    InnerProduct,
+}
+
+impl DistanceMetric {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DistanceMetric::Cosine => "cosine",
+            DistanceMetric::L2 => "l2",
+            DistanceMetric::InnerProduct => "inner_product",
+        }
+    }
+
+    /// pgvector distance operator
+    pub fn operator(&self) -> &'static str {
+        match self {
+            DistanceMetric::Cosine => "<=>",
+            DistanceMetric::L2 => "<->",
+            DistanceMetric::InnerProduct => "<#>",
+        }
+    }
+
+    /// Operator class an index must be built with to serve this metric
+    pub fn operator_class(&self) -> &'static str {
+        match self {
+            DistanceMetric::Cosine => "vector_cosine_ops",
+            DistanceMetric::L2 => "vector_l2_ops",
+            DistanceMetric::InnerProduct => "vector_ip_ops",
+        }
+    }
+
+    /// SQL turning a distance into a similarity where higher is closer: cosine
+    /// similarity for cosine, the inner product for inner product (`<#>` returns
+    /// its negation) and `1 / (1 + distance)` for L2
+    pub fn similarity_sql(&self, distance: &str) -> String {
+        match self {
+            DistanceMetric::Cosine => format!("1 - ({})", distance),
+            DistanceMetric::L2 => format!("1 / (1 + ({}))", distance),
+            DistanceMetric::InnerProduct => format!("-({})", distance),
+        }
+    }
+
+    /// Distance between two vectors as pgvector computes it with
+    /// [`Self::operator`]
+    pub fn distance(&self, a: &[f32], b: &[f32]) -> f32 {
+        match self {
+            DistanceMetric::Cosine => 1.0 - vector::cosine_similarity(a, b),
+            DistanceMetric::L2 => a
+                .iter()
+                .zip(b)
+                .map(|(a, b)| (a - b) * (a - b))
+                .sum::<f32>()
+                .sqrt(),
+            DistanceMetric::InnerProduct => -vector::dot(a, b),
+        }
+    }
+
+    /// Similarity for a distance, the same as [`Self::similarity_sql`]
+    pub fn similarity(&self, distance: f32) -> f64 {
+        let distance = distance as f64;
+        match self {
+            DistanceMetric::Cosine => 1.0 - distance,
+            DistanceMetric::L2 => 1.0 / (1.0 + distance),
+            DistanceMetric::InnerProduct => -distance,
+        }
+    }
+}
+
+impl std::str::FromStr for DistanceMetric {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "cosine" => Ok(DistanceMetric::Cosine),
+            "l2" => Ok(DistanceMetric::L2),
+            "inner_product" => Ok(DistanceMetric::InnerProduct),
+            other => Err(format!(
+                "Unknown distance metric '{}' (expected cosine, l2 or inner_product)",
+                other
+            )),
+        }
+    }
+}
+
+/// How a vector query is run: the embedding space, the metric, and per-query
+/// ANN index tuning
+#[derive(Debug, Clone, Default)]
+pub struct VectorSearchOptions {
+    /// Named embedding space (model id) to search; `None` uses `card.embedding`
+    pub space: Option<String>,
+    pub metric: DistanceMetric,
+    /// `hnsw.ef_search`: size of the HNSW candidate list, trading speed for recall
+    pub ef_search: Option<u32>,
+    /// `ivfflat.probes`: number of IVFFlat lists scanned, trading speed for recall
+    pub probes: Option<u32>,
+}
+
+/// A named embedding space: the vectors produced by one embedding model
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct EmbeddingSpace {
+    pub model_id: String,
+    pub dimension: i32,
+    /// Number of cards with a vector in this space
+    pub card_count: i64,
+}
+
+/// ANN index types supported by pgvector, with their build parameters
+#[derive(Debug, Clone, Copy)]
+pub enum VectorIndexMethod {
+    Hnsw { m: u32, ef_construction: u32 },
+    IvfFlat { lists: u32 },
+}
+
+impl VectorIndexMethod {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            VectorIndexMethod::Hnsw { .. } => "hnsw",
+            VectorIndexMethod::IvfFlat { .. } => "ivfflat",
+        }
+    }
+}
+
+/// An existing vector index on `card.embedding`
+#[derive(Debug, Serialize, schemars::JsonSchema)]
+pub struct VectorIndex {
+    pub name: String,
+    /// Index access method, `hnsw` or `ivfflat`
+    pub method: String,
+    /// The `CREATE INDEX` statement, including operator class and parameters
+    pub definition: String,
+    pub size_bytes: i64,
+}
+
+/// A card ranked by hybrid lexical + vector search
+#[derive(Debug)]
+pub struct HybridMatch {
+    pub card: CardModel,
+    /// Weighted reciprocal rank fusion score
+    pub score: f64,
+    /// 1-based rank among the lexical matches, if matched
+    pub lexical_rank: Option<usize>,
+    /// 1-based rank among the vector neighbors, if matched
+    pub vector_rank: Option<usize>,
+}
+
 // A serializable version of CardModel for MCP responses
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, schemars::JsonSchema)]
 pub struct CardResponse {
     pub id: i32,
     pub name: String,
@@ -43,10 +279,13 @@ pub struct CardResponse {
     pub legalities: Option<serde_json::Value>,
     pub reserved: Option<bool>,
     pub game_changer: Option<bool>,
+    /// Per-color mana symbol counts derived from `mana_cost`
+    pub pips: Option<ManaPips>,
 }
 
 impl From<CardModel> for CardResponse {
     fn from(card: CardModel) -> Self {
+        let pips = card.mana_cost.as_deref().map(ManaPips::from_mana_cost);
         Self {
             id: card.id,
             name: card.name,
@@ -64,7 +303,60 @@ impl From<CardModel> for CardResponse {
             legalities: card.legalities,
             reserved: card.reserved,
             game_changer: card.game_changer,
+            pips,
+        }
+    }
+}
+
+/// Counts of colored (and colorless) mana symbols in a mana cost.
+///
+/// Hybrid symbols such as `{W/U}` count toward each of their colors and
+/// Phyrexian symbols such as `{B/P}` count toward their color, matching how
+/// devotion is calculated.
+#[derive(
+    Debug, Clone, Copy, Default, Deserialize, Serialize, PartialEq, Eq, schemars::JsonSchema,
+)]
+pub struct ManaPips {
+    pub white: u32,
+    pub blue: u32,
+    pub black: u32,
+    pub red: u32,
+    pub green: u32,
+    pub colorless: u32,
+}
+
+impl ManaPips {
+    /// Pips of the front face's mana cost; a double-faced card's back face
+    /// is cast separately and does not count
+    pub fn from_mana_cost(mana_cost: &str) -> Self {
+        let mut pips = Self::default();
+        let front = mana_cost.split(" // ").next().unwrap_or_default();
+        for symbol in front.split(['{', '}']).filter(|symbol| !symbol.is_empty()) {
+            for part in symbol.split('/') {
+                match part {
+                    "W" => pips.white += 1,
+                    "U" => pips.blue += 1,
+                    "B" => pips.black += 1,
+                    "R" => pips.red += 1,
+                    "G" => pips.green += 1,
+                    "C" => pips.colorless += 1,
+                    _ => {}
+                }
+            }
         }
+        pips
+    }
+
+    /// Pip counts keyed by the mana symbol they are counted from
+    pub fn by_symbol(&self) -> [(char, u32); 6] {
+        [
+            ('W', self.white),
+            ('U', self.blue),
+            ('B', self.black),
+            ('R', self.red),
+            ('G', self.green),
+            ('C', self.colorless),
+        ]
     }
 }
 
@@ -144,7 +436,7 @@ impl From<String> for CardType {
     }
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
 pub struct CardFilters {
     /// Filter cards by main type
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -152,13 +444,68 @@ pub struct CardFilters {
     /// Fields to search across when a query is provided - options are: name, type
     #[serde(skip_serializing_if = "Option::is_none")]
     pub fields: Option<Vec<String>>,
+    /// Minimum number of mana symbols of each color in the mana cost
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_pips: Option<ManaPips>,
+    /// Only match permanent cards (artifacts, battles, creatures, enchantments, lands, planeswalkers)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub permanents_only: Option<bool>,
+    /// Restrict matches to cards with these names (case-insensitive, front face names match double-faced cards)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub names: Option<Vec<String>>,
+    /// Restrict matches to cards with these IDs
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ids: Option<Vec<i32>>,
+    /// Only match cards legal (or restricted) in this format, e.g. "modern"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub legal_in: Option<String>,
+    /// Only match cards whose color identity is a subset of these colors
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub color_identity_within: Option<Vec<String>>,
+    /// Only match cards with at most this mana value
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_cmc: Option<f64>,
+    /// Exclude cards on the reserved list
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exclude_reserved: Option<bool>,
 }
 
-impl Default for CardFilters {
-    fn default() -> Self {
-        Self {
-            main_type: None,
-            fields: None,
-        }
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!(
+            (actual - expected).abs() < 1e-6,
+            "expected {expected}, got {actual}"
+        );
+    }
+
+    #[test]
+    fn cosine_distance_matches_pgvector() {
+        let metric = DistanceMetric::Cosine;
+        assert_close(metric.distance(&[1.0, 0.0], &[2.0, 0.0]) as f64, 0.0);
+        assert_close(metric.distance(&[1.0, 0.0], &[0.0, 3.0]) as f64, 1.0);
+        assert_close(metric.distance(&[1.0, 0.0], &[-1.0, 0.0]) as f64, 2.0);
+        assert_close(metric.similarity(0.25), 0.75);
+    }
+
+    #[test]
+    fn l2_distance_matches_pgvector() {
+        let metric = DistanceMetric::L2;
+        assert_close(metric.distance(&[0.0, 0.0], &[3.0, 4.0]) as f64, 5.0);
+        assert_close(metric.distance(&[1.0, 2.0], &[1.0, 2.0]) as f64, 0.0);
+        assert_close(metric.similarity(0.0), 1.0);
+        assert_close(metric.similarity(3.0), 0.25);
+    }
+
+    #[test]
+    fn inner_product_distance_is_negated_like_pgvector() {
+        // pgvector's `<#>` returns the negative inner product so that smaller
+        // distances are still better
+        let metric = DistanceMetric::InnerProduct;
+        assert_close(metric.distance(&[1.0, 2.0], &[3.0, 4.0]) as f64, -11.0);
+        assert_close(metric.distance(&[1.0, 0.0], &[-2.0, 0.0]) as f64, 2.0);
+        assert_close(metric.similarity(-11.0), 11.0);
     }
 }
@@ -1,9 +1,74 @@
-use super::model::{CardFilters, CardModel, CardType};
+use super::backfill::BackfillMode;
+use super::duplicates::SimilarPair;
+use super::graph::KnnEdge;
+use super::model::{
+    CardFilters, CardModel, CardType, DistanceMetric, EmbeddingSpace, SimilarCard, VectorIndex,
+    VectorIndexMethod, VectorSearchOptions,
+};
 use crate::error::Error;
-use sqlx::{PgPool, Row};
+use sqlx::{
+    postgres::{PgArguments, Postgres},
+    PgPool, Row,
+};
 use pgvector::Vector;
 
 const MAX_LIMIT: i64 = 1000;
+/// Most candidates loaded for one strictly-better comparison
+const MAX_COMPARABLE_CANDIDATES: i64 = 5000;
+
+/// Every card column except `embedding`, for queries that don't need the vector
+const CARD_COLUMNS: &str =
+    "id, name, main_type, type_line, oracle_text, keywords, cmc, mana_cost, \
+    colors, color_identity, power, toughness, games, legalities, reserved, game_changer";
+
+/// Text indexed for lexical search; the `card_lexical_document_idx` migration
+/// indexes this exact expression
+const LEXICAL_DOCUMENT: &str =
+    "coalesce(name, '') || ' ' || coalesce(type_line, '') || ' ' || coalesce(oracle_text, '')";
+
+/// Escapes `%`, `_` and the escape character itself so user text matches
+/// literally inside a `LIKE`/`ILIKE` pattern
+fn escape_like(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
+
+/// A positional parameter collected while building a dynamic query
+enum QueryParam {
+    Text(String),
+    Int(i32),
+    Float(f64),
+    TextArray(Vec<String>),
+    IntArray(Vec<i32>),
+}
+
+impl QueryParam {
+    fn bind<'q>(
+        self,
+        query: sqlx::query::Query<'q, Postgres, PgArguments>,
+    ) -> sqlx::query::Query<'q, Postgres, PgArguments> {
+        match self {
+            QueryParam::Text(value) => query.bind(value),
+            QueryParam::Int(value) => query.bind(value),
+            QueryParam::Float(value) => query.bind(value),
+            QueryParam::TextArray(value) => query.bind(value),
+            QueryParam::IntArray(value) => query.bind(value),
+        }
+    }
+}
+
+/// SQL expression counting the mana symbols in `mana_cost` that contain the given
+/// color letter. Hybrid symbols like `{B/G}` count toward both of their colors.
+/// Only the front face is counted, so a double-faced card's back face cost
+/// (`{1}{R} // {2}{R}`) does not add to its pips.
+fn pip_count_sql(symbol: char) -> String {
+    let front = "split_part(coalesce(mana_cost, ''), ' // ', 1)";
+    format!(
+        "(length({front}) - length(replace({front}, '{}', '')))",
+        symbol
+    )
+}
 
 #[derive(Clone)]
 pub struct CardRepository {
@@ -82,48 +147,145 @@ impl CardRepository {
         Self::row_to_card_model(&row)
     }
 
+    pub async fn get_by_names(&self, names: &[String]) -> Result<Vec<CardModel>, Error> {
+        let rows = sqlx::query("SELECT * FROM card WHERE name = ANY($1)")
+            .bind(names)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| {
+                tracing::error!("Database query error: {:?}", e);
+                Error::InternalServerError
+            })?;
+
+        rows.iter().map(Self::row_to_card_model).collect()
+    }
+
+    /// Cards whose name, or the front face of whose split or double-faced
+    /// name, matches one of `names` case-insensitively
+    pub async fn get_by_names_or_front_faces(
+        &self,
+        names: &[String],
+    ) -> Result<Vec<CardModel>, Error> {
+        let names: Vec<String> = names.iter().map(|name| name.to_lowercase()).collect();
+        let rows = sqlx::query(&format!(
+            "SELECT {CARD_COLUMNS} FROM card
+            WHERE lower(name) = ANY($1) OR lower(split_part(name, ' // ', 1)) = ANY($1)
+            ORDER BY id"
+        ))
+        .bind(&names)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| {
+            tracing::error!("Database query error: {:?}", e);
+            Error::InternalServerError
+        })?;
+
+        rows.iter().map(Self::row_to_card_model).collect()
+    }
+
     fn build_where_conditions(
         &self,
         filters: &CardFilters,
         query: Option<&str>,
-    ) -> (String, Vec<String>) {
+    ) -> (String, Vec<QueryParam>) {
+        let mut conditions = vec!["1=1".to_string()];
         let mut params = Vec::new();
 
-        let where_clause = format!(
-            "1=1{}",
-            if let Some(query_str) = query {
-                // If fields are specified, search across those fields
-                if let Some(fields) = &filters.fields {
-                    let field_conditions: Vec<String> = fields
-                        .iter()
-                        .map(|field| {
-                            params.push(format!("%{}%", query_str));
-                            match field.to_lowercase().as_str() {
-                                "name" => format!("name ILIKE ${}", params.len()),
-                                "type" => format!("main_type ILIKE ${}", params.len()),
-                                _ => format!("name ILIKE ${}", params.len()), // fallback to name
-                            }
-                        })
-                        .collect();
-                    format!(" AND ({})", field_conditions.join(" OR "))
-                } else {
-                    params.push(format!("%{}%", query_str));
-                    format!(" AND name ILIKE ${}", params.len())
-                }
+        if let Some(query_str) = query {
+            // If fields are specified, search across those fields
+            if let Some(fields) = &filters.fields {
+                let field_conditions: Vec<String> = fields
+                    .iter()
+                    .map(|field| {
+                        params.push(QueryParam::Text(format!("%{}%", query_str)));
+                        match field.to_lowercase().as_str() {
+                            "name" => format!("name ILIKE ${}", params.len()),
+                            "type" => format!("main_type ILIKE ${}", params.len()),
+                            _ => format!("name ILIKE ${}", params.len()), // fallback to name
+                        }
+                    })
+                    .collect();
+                conditions.push(format!("({})", field_conditions.join(" OR ")));
             } else {
-                String::new()
+                params.push(QueryParam::Text(format!("%{}%", query_str)));
+                conditions.push(format!("name ILIKE ${}", params.len()));
             }
-        ) + &format!(
-            "{}",
-            if let Some(main_type) = &filters.main_type {
-                params.push(main_type.as_str().to_string());
-                format!(" AND main_type = ${}", params.len())
-            } else {
-                String::new()
+        }
+
+        if let Some(main_type) = &filters.main_type {
+            params.push(QueryParam::Text(main_type.as_str().to_string()));
+            conditions.push(format!("main_type = ${}", params.len()));
+        }
+
+        if let Some(min_pips) = &filters.min_pips {
+            for (symbol, count) in min_pips.by_symbol() {
+                if count > 0 {
+                    params.push(QueryParam::Int(count as i32));
+                    conditions.push(format!("{} >= ${}", pip_count_sql(symbol), params.len()));
+                }
             }
-        );
+        }
+
+        if filters.permanents_only.unwrap_or(false) {
+            conditions.push(
+                "type_line ~* '\\m(artifact|battle|creature|enchantment|land|planeswalker)\\M'"
+                    .to_string(),
+            );
+        }
+
+        if let Some(format) = &filters.legal_in {
+            params.push(QueryParam::Text(format.trim().to_lowercase()));
+            conditions.push(format!(
+                "legalities->>${} IN ('legal', 'restricted')",
+                params.len()
+            ));
+        }
 
-        (where_clause, params)
+        if let Some(colors) = &filters.color_identity_within {
+            params.push(QueryParam::TextArray(
+                colors
+                    .iter()
+                    .map(|color| color.trim().to_uppercase())
+                    .collect(),
+            ));
+            conditions.push(format!(
+                "coalesce(color_identity::text[], '{{}}') <@ ${}",
+                params.len()
+            ));
+        }
+
+        if let Some(max_cmc) = filters.max_cmc {
+            params.push(QueryParam::Float(max_cmc));
+            conditions.push(format!("cmc <= ${}", params.len()));
+        }
+
+        if filters.exclude_reserved.unwrap_or(false) {
+            conditions.push("reserved IS NOT TRUE".to_string());
+        }
+
+        // A supplied card list matches cards named in it or with a listed ID
+        let mut scope_conditions = Vec::new();
+        if let Some(names) = &filters.names {
+            params.push(QueryParam::TextArray(
+                names
+                    .iter()
+                    .map(|name| name.trim().to_lowercase())
+                    .collect(),
+            ));
+            scope_conditions.push(format!(
+                "lower(name) = ANY(${0}) OR lower(split_part(name, ' // ', 1)) = ANY(${0})",
+                params.len()
+            ));
+        }
+        if let Some(ids) = &filters.ids {
+            params.push(QueryParam::IntArray(ids.clone()));
+            scope_conditions.push(format!("id = ANY(${})", params.len()));
+        }
+        if !scope_conditions.is_empty() {
+            conditions.push(format!("({})", scope_conditions.join(" OR ")));
+        }
+
+        (conditions.join(" AND "), params)
     }
 
     pub async fn search(
@@ -157,7 +319,7 @@ impl CardRepository {
         let mut query_builder = sqlx::query(&query);
 
         for param in params {
-            query_builder = query_builder.bind(param);
+            query_builder = param.bind(query_builder);
         }
 
         query_builder = query_builder.bind(limit);
@@ -174,6 +336,41 @@ impl CardRepository {
         cards
     }
 
+    /// Full-text search over name, type line and oracle text, ranked by relevance.
+    /// Cards whose name contains the query rank above text-only matches. The
+    /// full-text match uses the `card_lexical_document_idx` expression index.
+    pub async fn lexical_search(&self, query: &str, limit: i64) -> Result<Vec<CardModel>, Error> {
+        let sql = format!(
+            "SELECT {columns}
+            FROM card
+            WHERE id IN (
+                SELECT id FROM card
+                WHERE to_tsvector('english', {document}) @@ websearch_to_tsquery('english', $1)
+                UNION
+                SELECT id FROM card WHERE name ILIKE $2
+            )
+            ORDER BY (name ILIKE $2) DESC,
+                ts_rank(to_tsvector('english', {document}), websearch_to_tsquery('english', $1)) DESC,
+                name
+            LIMIT $3",
+            columns = CARD_COLUMNS,
+            document = LEXICAL_DOCUMENT
+        );
+
+        let rows = sqlx::query(&sql)
+            .bind(query)
+            .bind(format!("%{}%", escape_like(query)))
+            .bind(limit)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| {
+                tracing::error!("Database query error: {:?}", e);
+                Error::InternalServerError
+            })?;
+
+        rows.iter().map(Self::row_to_card_model).collect()
+    }
+
     pub async fn count(&self) -> Result<i64, Error> {
         let result = sqlx::query!("SELECT COUNT(*) as count FROM card")
             .fetch_one(&self.pool)
@@ -183,34 +380,181 @@ impl CardRepository {
         Ok(result.count.unwrap_or(0))
     }
 
-    pub async fn find_similar_cards(
+    /// Cards whose embeddings are closest to a query vector with their similarity
+    /// to it, excluding the named cards (typically the seeds the query vector was
+    /// built from). Searches `card.embedding`, or the named embedding space given
+    /// in the options.
+    pub async fn search_by_embedding(
         &self,
-        card_name: &str,
+        embedding: Vector,
+        exclude_names: &[String],
+        filters: Option<CardFilters>,
         limit: Option<i64>,
-    ) -> Result<Vec<CardModel>, Error> {
+        options: &VectorSearchOptions,
+    ) -> Result<Vec<SimilarCard>, Error> {
         let limit = limit.unwrap_or(10);
+        let filters = filters.unwrap_or_default();
+        let (where_clause, mut params) = self.build_where_conditions(&filters, None);
 
-        // First, get the target card's embedding
-        let target_card = self.get_by_name(card_name).await?;
-        
-        let embedding = target_card.embedding.ok_or_else(|| {
-            Error::NotFound(format!("Card '{}' does not have an embedding", card_name))
-        })?;
+        let (source, embedding_column) = match &options.space {
+            Some(model_id) => {
+                params.push(QueryParam::Text(model_id.clone()));
+                (
+                    format!(
+                        "card JOIN card_embedding space ON space.card_id = card.id AND space.model_id = ${}",
+                        params.len()
+                    ),
+                    "space.embedding",
+                )
+            }
+            None => ("card".to_string(), "card.embedding"),
+        };
 
-        // Use pgvector's cosine distance to find similar cards
-        // We exclude the target card itself and order by similarity
-        let query = r#"
-            SELECT * FROM card 
-            WHERE embedding IS NOT NULL 
-            AND name != $1
-            ORDER BY embedding <=> $2
-            LIMIT $3
-        "#;
-
-        let rows = sqlx::query(query)
-            .bind(card_name)
+        // Order by the bare distance expression so a matching ANN index can be used.
+        // Filters are applied in the same query so the limit is still honored.
+        let distance = format!(
+            "{} {} ${}",
+            embedding_column,
+            options.metric.operator(),
+            params.len() + 2
+        );
+        let query = format!(
+            "SELECT {columns}, {embedding_column} AS embedding, {similarity} AS similarity
+            FROM {source}
+            WHERE {embedding_column} IS NOT NULL
+            AND {where_clause}
+            AND name <> ALL(${exclude})
+            ORDER BY {distance}
+            LIMIT ${limit}",
+            columns = CARD_COLUMNS,
+            embedding_column = embedding_column,
+            similarity = options.metric.similarity_sql(&distance),
+            source = source,
+            where_clause = where_clause,
+            exclude = params.len() + 1,
+            distance = distance,
+            limit = params.len() + 3
+        );
+
+        let mut query_builder = sqlx::query(&query);
+
+        for param in params {
+            query_builder = param.bind(query_builder);
+        }
+
+        let db_error = |e: sqlx::Error| {
+            tracing::error!("Database query error: {:?}", e);
+            Error::InternalServerError
+        };
+
+        // Index tuning is scoped to a transaction so it only affects this query
+        let mut transaction = self.pool.begin().await.map_err(db_error)?;
+        for (setting, value) in [
+            ("hnsw.ef_search", options.ef_search),
+            ("ivfflat.probes", options.probes),
+        ] {
+            if let Some(value) = value {
+                sqlx::query("SELECT set_config($1, $2, true)")
+                    .bind(setting)
+                    .bind(value.to_string())
+                    .execute(&mut *transaction)
+                    .await
+                    .map_err(db_error)?;
+            }
+        }
+
+        let rows = query_builder
+            .bind(exclude_names)
             .bind(embedding)
             .bind(limit)
+            .fetch_all(&mut *transaction)
+            .await
+            .map_err(db_error)?;
+        transaction.commit().await.map_err(db_error)?;
+
+        rows.iter()
+            .map(|row| {
+                Ok(SimilarCard {
+                    card: Self::row_to_card_model(row)?,
+                    similarity: row
+                        .try_get("similarity")
+                        .map_err(|_| Error::InternalServerError)?,
+                })
+            })
+            .collect()
+    }
+
+    /// Whether the pgvector `vector` extension is installed in the database
+    pub async fn vector_extension_installed(&self) -> Result<bool, Error> {
+        sqlx::query_scalar("SELECT EXISTS (SELECT 1 FROM pg_extension WHERE extname = 'vector')")
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| {
+                tracing::error!("Database query error: {:?}", e);
+                Error::InternalServerError
+            })
+    }
+
+    /// Number of dimensions of the stored `card.embedding` vectors, or of the
+    /// named embedding space, or `None` if nothing is stored there
+    pub async fn embedding_dimension(&self, space: Option<&str>) -> Result<Option<i32>, Error> {
+        let dimension: Result<Option<Option<i32>>, sqlx::Error> = match space {
+            Some(model_id) => {
+                sqlx::query_scalar("SELECT dimension FROM embedding_space WHERE model_id = $1")
+                    .bind(model_id)
+                    .fetch_optional(&self.pool)
+                    .await
+            }
+            None => {
+                sqlx::query_scalar(
+                    "SELECT array_length(embedding::real[], 1) FROM card
+                    WHERE embedding IS NOT NULL LIMIT 1",
+                )
+                .fetch_optional(&self.pool)
+                .await
+            }
+        };
+        dimension.map(Option::flatten).map_err(|e| {
+            tracing::error!("Database query error: {:?}", e);
+            Error::InternalServerError
+        })
+    }
+
+    /// Every `card.embedding` as plain floats, by card ID. Reads the column as
+    /// `real[]`, so it also works without the `vector` extension.
+    pub async fn all_embeddings(&self) -> Result<Vec<(i32, Vec<f32>)>, Error> {
+        sqlx::query_as(
+            "SELECT id, embedding::real[] FROM card WHERE embedding IS NOT NULL ORDER BY id",
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| {
+            tracing::error!("Database query error: {:?}", e);
+            Error::InternalServerError
+        })
+    }
+
+    /// IDs of the cards matching `filters` and not named in `exclude_names`,
+    /// for restricting an in-memory vector search
+    pub async fn filtered_card_ids(
+        &self,
+        filters: &CardFilters,
+        exclude_names: &[String],
+    ) -> Result<Vec<i32>, Error> {
+        let (where_clause, params) = self.build_where_conditions(filters, None);
+        let query = format!(
+            "SELECT id FROM card WHERE {} AND name <> ALL(${})",
+            where_clause,
+            params.len() + 1
+        );
+
+        let mut query_builder = sqlx::query(&query);
+        for param in params {
+            query_builder = param.bind(query_builder);
+        }
+
+        let rows = query_builder
+            .bind(exclude_names)
             .fetch_all(&self.pool)
             .await
             .map_err(|e| {
@@ -218,9 +562,616 @@ impl CardRepository {
                 Error::InternalServerError
             })?;
 
-        let cards: Result<Vec<CardModel>, Error> =
-            rows.iter().map(Self::row_to_card_model).collect();
+        rows.iter()
+            .map(|row| row.try_get("id").map_err(|_| Error::InternalServerError))
+            .collect()
+    }
 
-        cards
+    /// IDs of the named cards
+    pub async fn card_ids_by_names(&self, names: &[String]) -> Result<Vec<i32>, Error> {
+        sqlx::query_scalar("SELECT id FROM card WHERE name = ANY($1)")
+            .bind(names)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| {
+                tracing::error!("Database query error: {:?}", e);
+                Error::InternalServerError
+            })
+    }
+
+    /// Cards with the given IDs, in no particular order
+    pub async fn get_by_ids(&self, ids: &[i32]) -> Result<Vec<CardModel>, Error> {
+        let rows = sqlx::query(&format!(
+            "SELECT {CARD_COLUMNS} FROM card WHERE id = ANY($1)"
+        ))
+        .bind(ids)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| {
+            tracing::error!("Database query error: {:?}", e);
+            Error::InternalServerError
+        })?;
+
+        rows.iter().map(Self::row_to_card_model).collect()
+    }
+
+    /// Builds an ANN index on `card.embedding` for the given method and metric,
+    /// returning its name. Does nothing if an index of that name already exists.
+    pub async fn create_vector_index(
+        &self,
+        method: VectorIndexMethod,
+        metric: DistanceMetric,
+    ) -> Result<String, Error> {
+        // The name carries the build parameters, so `IF NOT EXISTS` only skips
+        // an index that is identical rather than one built with other settings
+        let (parameters, suffix) = match method {
+            VectorIndexMethod::Hnsw { m, ef_construction } => (
+                format!("m = {}, ef_construction = {}", m, ef_construction),
+                format!("m{}_ef{}", m, ef_construction),
+            ),
+            VectorIndexMethod::IvfFlat { lists } => {
+                (format!("lists = {}", lists), format!("lists{}", lists))
+            }
+        };
+        let name = format!(
+            "card_embedding_{}_{}_{}_idx",
+            method.as_str(),
+            metric.as_str(),
+            suffix
+        );
+        let statement = format!(
+            "CREATE INDEX IF NOT EXISTS {} ON card USING {} (embedding {}) WITH ({})",
+            name,
+            method.as_str(),
+            metric.operator_class(),
+            parameters
+        );
+
+        sqlx::query(&statement)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| {
+                tracing::error!("Failed to create vector index {}: {:?}", name, e);
+                Error::InternalServerError
+            })?;
+
+        Ok(name)
+    }
+
+    /// HNSW and IVFFlat indexes on the card table
+    pub async fn list_vector_indexes(&self) -> Result<Vec<VectorIndex>, Error> {
+        let rows = sqlx::query(
+            "SELECT index_class.relname AS name,
+                access_method.amname AS method,
+                pg_get_indexdef(index_class.oid) AS definition,
+                pg_relation_size(index_class.oid) AS size_bytes
+            FROM pg_index
+            JOIN pg_class index_class ON index_class.oid = pg_index.indexrelid
+            JOIN pg_class table_class ON table_class.oid = pg_index.indrelid
+            JOIN pg_am access_method ON access_method.oid = index_class.relam
+            WHERE table_class.relname = 'card'
+            AND access_method.amname IN ('hnsw', 'ivfflat')
+            ORDER BY index_class.relname",
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| {
+            tracing::error!("Database query error: {:?}", e);
+            Error::InternalServerError
+        })?;
+
+        rows.iter()
+            .map(|row| {
+                Ok(VectorIndex {
+                    name: row
+                        .try_get("name")
+                        .map_err(|_| Error::InternalServerError)?,
+                    method: row
+                        .try_get("method")
+                        .map_err(|_| Error::InternalServerError)?,
+                    definition: row
+                        .try_get("definition")
+                        .map_err(|_| Error::InternalServerError)?,
+                    size_bytes: row
+                        .try_get("size_bytes")
+                        .map_err(|_| Error::InternalServerError)?,
+                })
+            })
+            .collect()
+    }
+
+    /// Drops a vector index by name. Only indexes reported by
+    /// [`Self::list_vector_indexes`] can be dropped.
+    pub async fn drop_vector_index(&self, name: &str) -> Result<(), Error> {
+        let indexes = self.list_vector_indexes().await?;
+        if !indexes.iter().any(|index| index.name == name) {
+            return Err(Error::NotFound(format!("Vector index {}", name)));
+        }
+
+        sqlx::query(&format!(
+            "DROP INDEX IF EXISTS \"{}\"",
+            name.replace('"', "\"\"")
+        ))
+        .execute(&self.pool)
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to drop vector index {}: {:?}", name, e);
+            Error::InternalServerError
+        })?;
+
+        Ok(())
+    }
+
+    /// Cards matching the filters that have an embedding, in ID order
+    pub async fn search_with_embeddings(
+        &self,
+        filters: Option<CardFilters>,
+        limit: i64,
+    ) -> Result<Vec<CardModel>, Error> {
+        let filters = filters.unwrap_or_default();
+        let (where_clause, params) = self.build_where_conditions(&filters, None);
+        let query = format!(
+            "SELECT * FROM card
+            WHERE embedding IS NOT NULL
+            AND {}
+            ORDER BY id
+            LIMIT ${}",
+            where_clause,
+            params.len() + 1
+        );
+
+        let mut query_builder = sqlx::query(&query);
+        for param in params {
+            query_builder = param.bind(query_builder);
+        }
+
+        let rows = query_builder
+            .bind(limit)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| {
+                tracing::error!("Database query error: {:?}", e);
+                Error::InternalServerError
+            })?;
+
+        rows.iter().map(Self::row_to_card_model).collect()
+    }
+
+    /// Cards with an embedding matching `filters`, at most `max_cards` by ID,
+    /// without their embeddings: the nodes of a kNN graph
+    pub async fn knn_nodes(
+        &self,
+        filters: &CardFilters,
+        max_cards: i64,
+    ) -> Result<Vec<CardModel>, Error> {
+        let (where_clause, params) = self.build_where_conditions(filters, None);
+        let query = format!(
+            "SELECT {CARD_COLUMNS} FROM card
+            WHERE embedding IS NOT NULL
+            AND {}
+            ORDER BY id
+            LIMIT ${}",
+            where_clause,
+            params.len() + 1
+        );
+
+        let mut query_builder = sqlx::query(&query);
+        for param in params {
+            query_builder = param.bind(query_builder);
+        }
+
+        let rows = query_builder
+            .bind(max_cards)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| {
+                tracing::error!("Database query error: {:?}", e);
+                Error::InternalServerError
+            })?;
+
+        rows.iter().map(Self::row_to_card_model).collect()
+    }
+
+    /// The `k` nearest neighbors of each card in `ids` among the other cards in
+    /// `ids`, found with one lateral join rather than a query per card.
+    /// `ef_search` should be well above `k`, as an HNSW index returns its
+    /// candidates before the ID filter is applied.
+    pub async fn knn_edges(
+        &self,
+        ids: &[i32],
+        k: i64,
+        metric: DistanceMetric,
+        ef_search: u32,
+    ) -> Result<Vec<KnnEdge>, Error> {
+        let distance = format!("neighbor.embedding {} source.embedding", metric.operator());
+        let query = format!(
+            "SELECT source.id AS source, neighbor.id AS target, neighbor.similarity
+            FROM card source
+            CROSS JOIN LATERAL (
+                SELECT neighbor.id, {similarity} AS similarity
+                FROM card neighbor
+                WHERE neighbor.embedding IS NOT NULL
+                AND neighbor.id = ANY($1) AND neighbor.id <> source.id
+                ORDER BY {distance}
+                LIMIT $2
+            ) neighbor
+            WHERE source.embedding IS NOT NULL AND source.id = ANY($1)
+            ORDER BY source.id, neighbor.similarity DESC",
+            similarity = metric.similarity_sql(&distance),
+            distance = distance,
+        );
+
+        let db_error = |e: sqlx::Error| {
+            tracing::error!("Database query error: {:?}", e);
+            Error::InternalServerError
+        };
+
+        let mut transaction = self.pool.begin().await.map_err(db_error)?;
+        sqlx::query("SELECT set_config('hnsw.ef_search', $1, true)")
+            .bind(ef_search.to_string())
+            .execute(&mut *transaction)
+            .await
+            .map_err(db_error)?;
+        let rows = sqlx::query(&query)
+            .bind(ids)
+            .bind(k)
+            .fetch_all(&mut *transaction)
+            .await
+            .map_err(db_error)?;
+        transaction.commit().await.map_err(db_error)?;
+
+        rows.iter()
+            .map(|row| {
+                Ok(KnnEdge {
+                    source: row
+                        .try_get("source")
+                        .map_err(|_| Error::InternalServerError)?,
+                    target: row
+                        .try_get("target")
+                        .map_err(|_| Error::InternalServerError)?,
+                    similarity: row
+                        .try_get("similarity")
+                        .map_err(|_| Error::InternalServerError)?,
+                })
+            })
+            .collect()
+    }
+
+    /// Lowest and highest card IDs, or `None` for an empty table
+    pub async fn card_id_range(&self) -> Result<Option<(i32, i32)>, Error> {
+        let (min_id, max_id): (Option<i32>, Option<i32>) =
+            sqlx::query_as("SELECT min(id), max(id) FROM card")
+                .fetch_one(&self.pool)
+                .await
+                .map_err(|e| {
+                    tracing::error!("Database query error: {:?}", e);
+                    Error::InternalServerError
+                })?;
+        Ok(min_id.zip(max_id))
+    }
+
+    /// Pairs of cards with a cosine similarity of at least `threshold`, checking
+    /// the `max_neighbors` nearest neighbors of each card with an ID in
+    /// `(after_id, up_to_id]`. A pair may be returned in both directions.
+    pub async fn near_duplicate_pairs(
+        &self,
+        threshold: f64,
+        max_neighbors: i64,
+        after_id: i32,
+        up_to_id: i32,
+    ) -> Result<Vec<SimilarPair>, Error> {
+        let rows = sqlx::query(
+            "SELECT source.id AS first_id, source.name AS first_name,
+                neighbor.id AS second_id, neighbor.name AS second_name,
+                1 - (source.embedding <=> neighbor.embedding) AS similarity
+            FROM card source
+            CROSS JOIN LATERAL (
+                SELECT id, name, embedding
+                FROM card
+                WHERE embedding IS NOT NULL AND id <> source.id
+                ORDER BY embedding <=> source.embedding
+                LIMIT $3
+            ) neighbor
+            WHERE source.embedding IS NOT NULL
+            AND source.id > $1 AND source.id <= $2
+            AND 1 - (source.embedding <=> neighbor.embedding) >= $4",
+        )
+        .bind(after_id)
+        .bind(up_to_id)
+        .bind(max_neighbors)
+        .bind(threshold)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| {
+            tracing::error!("Database query error: {:?}", e);
+            Error::InternalServerError
+        })?;
+
+        rows.iter()
+            .map(|row| {
+                Ok(SimilarPair {
+                    first_id: row
+                        .try_get("first_id")
+                        .map_err(|_| Error::InternalServerError)?,
+                    first_name: row
+                        .try_get("first_name")
+                        .map_err(|_| Error::InternalServerError)?,
+                    second_id: row
+                        .try_get("second_id")
+                        .map_err(|_| Error::InternalServerError)?,
+                    second_name: row
+                        .try_get("second_name")
+                        .map_err(|_| Error::InternalServerError)?,
+                    similarity: row
+                        .try_get("similarity")
+                        .map_err(|_| Error::InternalServerError)?,
+                })
+            })
+            .collect()
+    }
+
+    /// SQL condition selecting the cards a backfill in the given mode recomputes.
+    /// For a named embedding space, whose dimension is fixed, stale and missing
+    /// cards are the same; its model id is bound as parameter `$space_param`.
+    fn backfill_condition(
+        mode: BackfillMode,
+        dimension: usize,
+        space: Option<&str>,
+        space_param: usize,
+    ) -> String {
+        match (mode, space) {
+            (BackfillMode::All, _) => "TRUE".to_string(),
+            (_, Some(_)) => format!(
+                "NOT EXISTS (SELECT 1 FROM card_embedding WHERE card_embedding.card_id = card.id AND card_embedding.model_id = ${})",
+                space_param
+            ),
+            (BackfillMode::Missing, None) => "embedding IS NULL".to_string(),
+            (BackfillMode::Stale, None) => format!(
+                "(embedding IS NULL OR vector_dims(embedding) <> {})",
+                dimension
+            ),
+        }
+    }
+
+    /// Number of cards after `after_id` a backfill in the given mode would
+    /// process, for `card.embedding` or a named embedding space
+    pub async fn count_cards_to_embed(
+        &self,
+        mode: BackfillMode,
+        dimension: usize,
+        space: Option<&str>,
+        after_id: Option<i32>,
+    ) -> Result<i64, Error> {
+        let query = format!(
+            "SELECT COUNT(*) FROM card WHERE {} AND id > $1",
+            Self::backfill_condition(mode, dimension, space, 2)
+        );
+
+        sqlx::query_scalar(&query)
+            .bind(after_id.unwrap_or(i32::MIN))
+            .bind(space)
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| {
+                tracing::error!("Database query error: {:?}", e);
+                Error::InternalServerError
+            })
+    }
+
+    /// The next batch of cards, in ID order, that a backfill in the given mode
+    /// would process
+    pub async fn cards_to_embed(
+        &self,
+        mode: BackfillMode,
+        dimension: usize,
+        space: Option<&str>,
+        after_id: Option<i32>,
+        limit: i64,
+    ) -> Result<Vec<CardModel>, Error> {
+        let query = format!(
+            "SELECT {} FROM card WHERE {} AND id > $1 ORDER BY id LIMIT $2",
+            CARD_COLUMNS,
+            Self::backfill_condition(mode, dimension, space, 3)
+        );
+
+        let rows = sqlx::query(&query)
+            .bind(after_id.unwrap_or(i32::MIN))
+            .bind(limit)
+            .bind(space)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| {
+                tracing::error!("Database query error: {:?}", e);
+                Error::InternalServerError
+            })?;
+
+        rows.iter().map(Self::row_to_card_model).collect()
+    }
+
+    /// Writes card embeddings in a single transaction, to `card.embedding` or to
+    /// the named embedding space
+    pub async fn update_embeddings(
+        &self,
+        space: Option<&EmbeddingSpace>,
+        embeddings: Vec<(i32, Vector)>,
+    ) -> Result<(), Error> {
+        let db_error = |e: sqlx::Error| {
+            tracing::error!("Failed to write embeddings: {:?}", e);
+            Error::InternalServerError
+        };
+
+        let mut transaction = self.pool.begin().await.map_err(db_error)?;
+        for (id, embedding) in embeddings {
+            let query = match space {
+                Some(space) => sqlx::query(
+                    "INSERT INTO card_embedding (card_id, embedding, model_id, dimension)
+                    VALUES ($1, $2, $3, $4)
+                    ON CONFLICT (model_id, card_id)
+                    DO UPDATE SET embedding = EXCLUDED.embedding, updated_at = now()",
+                )
+                .bind(id)
+                .bind(embedding)
+                .bind(&space.model_id)
+                .bind(space.dimension),
+                None => sqlx::query("UPDATE card SET embedding = $2 WHERE id = $1")
+                    .bind(id)
+                    .bind(embedding),
+            };
+            query.execute(&mut *transaction).await.map_err(db_error)?;
+        }
+        transaction.commit().await.map_err(db_error)
+    }
+
+    /// Registers a named embedding space, failing if the model id is already
+    /// registered with another dimension
+    pub async fn ensure_embedding_space(
+        &self,
+        model_id: &str,
+        dimension: i32,
+    ) -> Result<EmbeddingSpace, Error> {
+        sqlx::query(
+            "INSERT INTO embedding_space (model_id, dimension) VALUES ($1, $2)
+            ON CONFLICT (model_id) DO NOTHING",
+        )
+        .bind(model_id)
+        .bind(dimension)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to register embedding space {}: {:?}", model_id, e);
+            Error::InternalServerError
+        })?;
+
+        let space = self
+            .list_embedding_spaces()
+            .await?
+            .into_iter()
+            .find(|space| space.model_id == model_id)
+            .ok_or(Error::InternalServerError)?;
+        if space.dimension != dimension {
+            return Err(Error::BadRequest(format!(
+                "Embedding space {} has {} dimensions, not {}",
+                model_id, space.dimension, dimension
+            )));
+        }
+        Ok(space)
+    }
+
+    /// Registered embedding spaces with the number of cards embedded in each
+    pub async fn list_embedding_spaces(&self) -> Result<Vec<EmbeddingSpace>, Error> {
+        let rows = sqlx::query(
+            "SELECT embedding_space.model_id, embedding_space.dimension,
+                COUNT(card_embedding.card_id) AS card_count
+            FROM embedding_space
+            LEFT JOIN card_embedding ON card_embedding.model_id = embedding_space.model_id
+            GROUP BY embedding_space.model_id, embedding_space.dimension
+            ORDER BY embedding_space.model_id",
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| {
+            tracing::error!("Database query error: {:?}", e);
+            Error::InternalServerError
+        })?;
+
+        rows.iter()
+            .map(|row| {
+                Ok(EmbeddingSpace {
+                    model_id: row
+                        .try_get("model_id")
+                        .map_err(|_| Error::InternalServerError)?,
+                    dimension: row
+                        .try_get("dimension")
+                        .map_err(|_| Error::InternalServerError)?,
+                    card_count: row
+                        .try_get("card_count")
+                        .map_err(|_| Error::InternalServerError)?,
+                })
+            })
+            .collect()
+    }
+
+    /// Embeddings of the named cards in a named embedding space, keyed by card
+    /// name. Cards without a vector in the space are left out.
+    pub async fn get_space_embeddings(
+        &self,
+        model_id: &str,
+        names: &[String],
+    ) -> Result<Vec<(String, Vector)>, Error> {
+        let rows = sqlx::query(
+            "SELECT card.name, card_embedding.embedding
+            FROM card
+            JOIN card_embedding ON card_embedding.card_id = card.id
+            WHERE card_embedding.model_id = $1 AND card.name = ANY($2)",
+        )
+        .bind(model_id)
+        .bind(names)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| {
+            tracing::error!("Database query error: {:?}", e);
+            Error::InternalServerError
+        })?;
+
+        rows.iter()
+            .map(|row| {
+                Ok((
+                    row.try_get("name")
+                        .map_err(|_| Error::InternalServerError)?,
+                    row.try_get("embedding")
+                        .map_err(|_| Error::InternalServerError)?,
+                ))
+            })
+            .collect()
+    }
+
+    /// Cards sharing the target's main type and legendary status that cost no
+    /// more, stay within its color identity and have at least its keywords, i.e.
+    /// the candidates for a strictly-better comparison. At most
+    /// `MAX_COMPARABLE_CANDIDATES` are returned, cheapest first.
+    pub async fn find_comparable_candidates(
+        &self,
+        target: &CardModel,
+    ) -> Result<Vec<CardModel>, Error> {
+        let query = format!(
+            "SELECT {}
+            FROM card
+            WHERE main_type = $1
+            AND name != $2
+            AND coalesce(cmc, 0) <= $3
+            AND coalesce(color_identity::text[], '{{}}') <@ $4
+            AND ARRAY(SELECT lower(keyword) FROM unnest(coalesce(keywords, '{{}}')) keyword) @> $5
+            AND (coalesce(type_line, '') LIKE '%Legendary%') = $6
+            ORDER BY cmc, name
+            LIMIT $7",
+            CARD_COLUMNS
+        );
+        let keywords: Vec<String> = target
+            .keywords
+            .iter()
+            .flatten()
+            .map(|keyword| keyword.to_lowercase())
+            .collect();
+        let legendary = target
+            .type_line
+            .as_deref()
+            .is_some_and(|type_line| type_line.contains("Legendary"));
+
+        let rows = sqlx::query(&query)
+            .bind(target.main_type.as_str())
+            .bind(&target.name)
+            .bind(target.cmc.unwrap_or(0.0))
+            .bind(target.color_identity.clone().unwrap_or_default())
+            .bind(keywords)
+            .bind(legendary)
+            .bind(MAX_COMPARABLE_CANDIDATES)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| {
+                tracing::error!("Database query error: {:?}", e);
+                Error::InternalServerError
+            })?;
+
+        rows.iter().map(Self::row_to_card_model).collect()
     }
 }
@@ -1,30 +1,180 @@
-use super::{model::CardModel, repository::CardRepository};
+use super::{
+    backfill::{self, BackfillOptions, BackfillProgress},
+    comparison::{self, BetterCards, CardComparison, Comparison},
+    duplicates::{self, DuplicateReport, SimilarPair},
+    evaluation::{self, EvaluationReport, GoldenSet},
+    graph::{KnnEdge, KnnGraph, KnnNode},
+    model::{
+        CardCluster, CardModel, CardProjection, DistanceMetric, EmbeddingSpace, HybridMatch,
+        ReplacementConstraints, Replacements, SimilarCard, SimilarCardsQuery, VectorIndex,
+        VectorIndexMethod, VectorSearchOptions,
+    },
+    repository::CardRepository,
+};
+use crate::similarity::{
+    ann::{self, AnnIndex, AnnIndexKind},
+    fusion::{self, RankedList},
+    kmeans, mmr, pca, vector,
+};
 use crate::{
-    cards::model::{CardFilters, CardType},
+    cards::{
+        mcp::SearchFilters,
+        model::{CardFilters, CardType, ManaPips},
+    },
+    embeddings::Embedder,
     error::Error,
 };
+use pgvector::Vector;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::Arc;
+use tokio::sync::{Mutex, RwLock};
+
+/// Default minimum similarity for near-duplicate detection
+pub const DEFAULT_DUPLICATE_THRESHOLD: f64 = 0.95;
+/// Default number of nearest neighbors checked per card for near-duplicates
+pub const DEFAULT_DUPLICATE_NEIGHBORS: i64 = 10;
+/// Card IDs scanned per near-duplicate query
+const DUPLICATE_BATCH_SIZE: i32 = 500;
+/// Most cards a kNN graph may hold, as each card runs its own neighbor search
+pub const MAX_GRAPH_CARDS: i64 = 5000;
+/// Most neighbors per card in a kNN graph
+pub const MAX_GRAPH_NEIGHBORS: i64 = 50;
+/// Smallest HNSW candidate list used when building a kNN graph, pgvector's
+/// default `hnsw.ef_search`
+const DEFAULT_GRAPH_EF_SEARCH: u32 = 40;
+/// Most cards projected or clustered at once
+pub const MAX_ANALYZED_CARDS: i64 = 20000;
 
 #[derive(Clone)]
 pub struct CardService {
     repository: CardRepository,
+    embedder: Option<Arc<dyn Embedder>>,
+    /// In-memory index over `card.embedding` that serves vector queries
+    /// instead of pgvector
+    memory_index: Option<Arc<dyn AnnIndex>>,
+    /// Latest near-duplicate report, shared by all clones of the service
+    duplicates: Arc<RwLock<Option<Arc<DuplicateReport>>>>,
+    /// Held while a near-duplicate scan runs, so scans never overlap
+    duplicate_scan: Arc<Mutex<()>>,
 }
 
 impl CardService {
     pub fn new(repository: CardRepository) -> Self {
-        Self { repository }
+        Self {
+            repository,
+            embedder: None,
+            memory_index: None,
+            duplicates: Arc::new(RwLock::new(None)),
+            duplicate_scan: Arc::new(Mutex::new(())),
+        }
+    }
+
+    /// Enables free-text semantic search using the given embedder
+    pub fn with_embedder(mut self, embedder: Arc<dyn Embedder>) -> Self {
+        self.embedder = Some(embedder);
+        self
+    }
+
+    /// Serves vector queries from an in-memory index instead of pgvector
+    pub fn with_memory_index(mut self, index: Arc<dyn AnnIndex>) -> Self {
+        self.memory_index = Some(index);
+        self
+    }
+
+    /// Builds an in-memory index of the given kind from every `card.embedding`
+    pub async fn build_memory_index(&self, kind: AnnIndexKind) -> Result<Arc<dyn AnnIndex>, Error> {
+        let embeddings = self.repository.all_embeddings().await?;
+        tokio::task::spawn_blocking(move || ann::build(kind, embeddings))
+            .await
+            .map_err(|e| {
+                tracing::error!("Failed to build the in-memory vector index: {:?}", e);
+                Error::InternalServerError
+            })
+    }
+
+    /// Checks a `max_cards` argument, which bounds how many cards a call
+    /// loads and processes
+    fn check_max_cards(max_cards: i64, most: i64) -> Result<(), Error> {
+        if max_cards <= 0 {
+            return Err(Error::BadRequest(
+                "max_cards must be greater than zero".to_string(),
+            ));
+        }
+        if max_cards > most {
+            return Err(Error::BadRequest(format!(
+                "max_cards must be at most {}",
+                most
+            )));
+        }
+        Ok(())
+    }
+
+    fn card_filters(filters: &SearchFilters) -> CardFilters {
+        CardFilters {
+            main_type: filters.card_type.as_ref().map(|t| CardType::from_str(t)),
+            fields: filters.fields.clone(),
+            min_pips: filters.min_pips.as_ref().map(|pips| ManaPips {
+                white: pips.white.unwrap_or(0),
+                blue: pips.blue.unwrap_or(0),
+                black: pips.black.unwrap_or(0),
+                red: pips.red.unwrap_or(0),
+                green: pips.green.unwrap_or(0),
+                colorless: pips.colorless.unwrap_or(0),
+            }),
+            permanents_only: filters.permanents_only,
+            names: filters.card_names.clone(),
+            ids: filters.card_ids.clone(),
+            legal_in: filters.legal_in.clone(),
+            color_identity_within: filters.color_identity_within.clone(),
+            max_cmc: filters.max_cmc,
+            exclude_reserved: filters.exclude_reserved,
+        }
+    }
+
+    fn embedder(&self) -> Result<&Arc<dyn Embedder>, Error> {
+        self.embedder.as_ref().ok_or_else(|| {
+            Error::BadRequest(
+                "No embedder configured - set EMBEDDING_MODEL_PATH or EMBEDDER".to_string(),
+            )
+        })
+    }
+
+    /// Embeds free text with the configured embedder, checking that the vector
+    /// has as many dimensions as the stored embeddings it will be compared with.
+    /// A named space must hold the configured embedder's own vectors.
+    async fn embed_query(&self, query: &str, space: Option<&str>) -> Result<Vec<f32>, Error> {
+        let embedder = self.embedder()?;
+        if let Some(model_id) = space.filter(|&model_id| model_id != embedder.model_id()) {
+            return Err(Error::BadRequest(format!(
+                "Embedding space {} is not the space of the configured embedder {}",
+                model_id,
+                embedder.model_id()
+            )));
+        }
+        self.ensure_space_available(space)?;
+        let stored = match &self.memory_index {
+            Some(index) if space.is_none() => (!index.is_empty()).then(|| index.dimension() as i32),
+            _ => self.repository.embedding_dimension(space).await?,
+        };
+        if let Some(stored) = stored.filter(|&stored| stored != embedder.dimension() as i32) {
+            return Err(Error::BadRequest(format!(
+                "Embedder {} produces {} dimensions but the stored card embeddings have {}",
+                embedder.model_id(),
+                embedder.dimension(),
+                stored
+            )));
+        }
+        embedder.embed(query)
     }
 
     pub async fn search_cards(
         &self,
-        filters: &crate::cards::mcp::SearchFilters,
+        filters: &SearchFilters,
         query: Option<&str>,
         limit: Option<i32>,
         offset: Option<i32>,
     ) -> Result<Vec<CardModel>, Error> {
-        let mut card_filters = CardFilters {
-            main_type: filters.card_type.as_ref().map(|t| CardType::from_str(t)),
-            fields: filters.fields.clone(),
-        };
+        let mut card_filters = Self::card_filters(filters);
 
         // If a query is provided but no fields specified, default to searching name
         if query.is_some() && card_filters.fields.is_none() {
@@ -49,13 +199,994 @@ impl CardService {
         self.repository.count().await
     }
 
+    /// Finds cards resembling the seed cards and, when negative cards are given,
+    /// unlike them. The query vector is the mean direction of the seed embeddings
+    /// minus `negative_weight` times the mean direction of the negative ones.
     pub async fn find_similar_cards(
         &self,
-        card_name: &str,
+        query: &SimilarCardsQuery,
+        filters: Option<&SearchFilters>,
+    ) -> Result<Vec<SimilarCard>, Error> {
+        if query.card_names.is_empty() {
+            return Err(Error::BadRequest(
+                "At least one seed card is required".to_string(),
+            ));
+        }
+        if query.limit <= 0 {
+            return Err(Error::BadRequest(format!(
+                "limit must be greater than zero, got {}",
+                query.limit
+            )));
+        }
+        if query.vector_options.ef_search == Some(0) || query.vector_options.probes == Some(0) {
+            return Err(Error::BadRequest(
+                "ef_search and probes must be greater than zero".to_string(),
+            ));
+        }
+        if !(0.0..=1.0).contains(&query.negative_weight) {
+            return Err(Error::BadRequest(format!(
+                "negative_weight must be between 0 and 1, got {}",
+                query.negative_weight
+            )));
+        }
+
+        let space = query.vector_options.space.as_deref();
+        let positive = self.seed_embeddings(&query.card_names, space).await?;
+        let negative = self
+            .seed_embeddings(&query.negative_card_names, space)
+            .await?;
+
+        let mut embedding =
+            vector::mean_direction(positive.iter().map(|v| v.as_slice())).unwrap_or_default();
+        if let Some(negative) = vector::mean_direction(negative.iter().map(|v| v.as_slice())) {
+            embedding
+                .iter_mut()
+                .zip(&negative)
+                .for_each(|(e, n)| *e -= query.negative_weight * n);
+            vector::normalize(&mut embedding);
+        }
+
+        let exclude_names: Vec<String> = query
+            .card_names
+            .iter()
+            .chain(&query.negative_card_names)
+            .cloned()
+            .collect();
+
+        // Diversify over a deeper pool of neighbors so there are distinct
+        // variants to choose from
+        let pool_size = if query.diversity > 0.0 {
+            (query.limit * 5).clamp(50, 500)
+        } else {
+            query.limit
+        };
+        let mut candidates = self
+            .nearest_cards(
+                embedding.clone(),
+                &exclude_names,
+                filters.map(Self::card_filters),
+                Some(pool_size),
+                &query.vector_options,
+            )
+            .await?;
+        if let Some(min_similarity) = query.min_similarity {
+            candidates.retain(|candidate| candidate.similarity >= min_similarity);
+        }
+        if query.diversity <= 0.0 {
+            return Ok(candidates);
+        }
+
+        let candidate_embeddings: Vec<Vec<f32>> = candidates
+            .iter()
+            .map(|candidate| {
+                candidate
+                    .card
+                    .embedding
+                    .as_ref()
+                    .map(Vector::to_vec)
+                    .unwrap_or_default()
+            })
+            .collect();
+        let candidate_slices: Vec<&[f32]> =
+            candidate_embeddings.iter().map(|v| v.as_slice()).collect();
+        let order = mmr::maximal_marginal_relevance(
+            &embedding,
+            &candidate_slices,
+            query.limit as usize,
+            query.diversity,
+        );
+
+        let mut candidates: Vec<Option<SimilarCard>> = candidates.into_iter().map(Some).collect();
+        Ok(order
+            .into_iter()
+            .filter_map(|index| candidates[index].take())
+            .collect())
+    }
+
+    /// Named embedding spaces live in pgvector columns, which the in-memory
+    /// index does not cover
+    fn ensure_space_available(&self, space: Option<&str>) -> Result<(), Error> {
+        match (space, &self.memory_index) {
+            (Some(model_id), Some(_)) => Err(Error::BadRequest(format!(
+                "Embedding space {} needs the vector extension; only card.embedding is searchable in memory",
+                model_id
+            ))),
+            _ => Ok(()),
+        }
+    }
+
+    /// Fails for features that run pgvector queries directly, which the
+    /// in-memory index cannot serve
+    fn ensure_pgvector(&self, feature: &str) -> Result<(), Error> {
+        match &self.memory_index {
+            Some(_) => Err(Error::BadRequest(format!(
+                "{} needs the vector extension and is unavailable while vector queries run in memory",
+                feature
+            ))),
+            None => Ok(()),
+        }
+    }
+
+    /// Fills in a card's embedding from the in-memory index, since pgvector
+    /// columns cannot be decoded without the extension
+    fn fill_embedding(&self, card: &mut CardModel) {
+        if card.embedding.is_none() {
+            if let Some(index) = &self.memory_index {
+                card.embedding = index.vector(card.id).map(|v| Vector::from(v.to_vec()));
+            }
+        }
+    }
+
+    /// Cards closest to a query vector, from the in-memory index if there is
+    /// one and from pgvector otherwise
+    async fn nearest_cards(
+        &self,
+        embedding: Vec<f32>,
+        exclude_names: &[String],
+        filters: Option<CardFilters>,
+        limit: Option<i64>,
+        options: &VectorSearchOptions,
+    ) -> Result<Vec<SimilarCard>, Error> {
+        let Some(index) = &self.memory_index else {
+            return self
+                .repository
+                .search_by_embedding(
+                    Vector::from(embedding),
+                    exclude_names,
+                    filters,
+                    limit,
+                    options,
+                )
+                .await;
+        };
+
+        self.ensure_space_available(options.space.as_deref())?;
+        if embedding.len() != index.dimension() {
+            return Err(Error::BadRequest(format!(
+                "Query vector has {} dimensions but card embeddings have {}",
+                embedding.len(),
+                index.dimension()
+            )));
+        }
+
+        // Filters are resolved to card IDs in SQL and applied during the search
+        // so the limit is still honored, as in the pgvector query
+        let allowed: Option<HashSet<i32>> = match &filters {
+            Some(filters) => Some(
+                self.repository
+                    .filtered_card_ids(filters, exclude_names)
+                    .await?
+                    .into_iter()
+                    .collect(),
+            ),
+            None => None,
+        };
+        let excluded: HashSet<i32> = if allowed.is_none() && !exclude_names.is_empty() {
+            self.repository
+                .card_ids_by_names(exclude_names)
+                .await?
+                .into_iter()
+                .collect()
+        } else {
+            HashSet::new()
+        };
+        let filter = |id: i32| {
+            allowed.as_ref().is_none_or(|allowed| allowed.contains(&id)) && !excluded.contains(&id)
+        };
+
+        let neighbors = index.search(
+            &embedding,
+            limit.unwrap_or(10).max(0) as usize,
+            options.metric,
+            options.ef_search.map(|ef_search| ef_search as usize),
+            &filter,
+        );
+        let ids: Vec<i32> = neighbors.iter().map(|neighbor| neighbor.id).collect();
+        let mut cards: HashMap<i32, CardModel> = self
+            .repository
+            .get_by_ids(&ids)
+            .await?
+            .into_iter()
+            .map(|card| (card.id, card))
+            .collect();
+
+        Ok(neighbors
+            .into_iter()
+            .filter_map(|neighbor| {
+                let mut card = cards.remove(&neighbor.id)?;
+                self.fill_embedding(&mut card);
+                Some(SimilarCard {
+                    card,
+                    similarity: options.metric.similarity(neighbor.distance),
+                })
+            })
+            .collect())
+    }
+
+    /// Embeddings of the named cards in `card.embedding` or the named embedding
+    /// space, failing if any card is unknown or has no embedding there
+    async fn seed_embeddings(
+        &self,
+        names: &[String],
+        space: Option<&str>,
+    ) -> Result<Vec<Vec<f32>>, Error> {
+        if names.is_empty() {
+            return Ok(Vec::new());
+        }
+        self.ensure_space_available(space)?;
+
+        let cards: HashMap<String, CardModel> = self
+            .repository
+            .get_by_names(names)
+            .await?
+            .into_iter()
+            .map(|card| (card.name.clone(), card))
+            .collect();
+        let space_embeddings: HashMap<String, Vector> = match space {
+            Some(model_id) => self
+                .repository
+                .get_space_embeddings(model_id, names)
+                .await?
+                .into_iter()
+                .collect(),
+            None => HashMap::new(),
+        };
+
+        names
+            .iter()
+            .map(|name| {
+                let card = cards
+                    .get(name)
+                    .ok_or_else(|| Error::NotFound(format!("Card with name {}", name)))?;
+                let embedding = match space {
+                    Some(_) => space_embeddings.get(name).map(Vector::to_vec),
+                    None => card.embedding.as_ref().map(Vector::to_vec).or_else(|| {
+                        self.memory_index
+                            .as_ref()
+                            .and_then(|index| index.vector(card.id))
+                            .map(<[f32]>::to_vec)
+                    }),
+                };
+                embedding.ok_or_else(|| match space {
+                    Some(model_id) => Error::NotFound(format!(
+                        "Card '{}' does not have an embedding in space {}",
+                        name, model_id
+                    )),
+                    None => Error::NotFound(format!("Card '{}' does not have an embedding", name)),
+                })
+            })
+            .collect()
+    }
+
+    pub async fn list_embedding_spaces(&self) -> Result<Vec<EmbeddingSpace>, Error> {
+        self.ensure_pgvector("Listing embedding spaces")?;
+        self.repository.list_embedding_spaces().await
+    }
+
+    /// Cards nearest to the embedded query in `card.embedding`, or in the
+    /// embedding space named in the options
+    pub async fn semantic_search(
+        &self,
+        query: &str,
         limit: Option<i32>,
+        options: &VectorSearchOptions,
     ) -> Result<Vec<CardModel>, Error> {
-        self.repository
-            .find_similar_cards(card_name, limit.map(|l| l as i64))
-            .await
+        let embedding = self.embed_query(query, options.space.as_deref()).await?;
+        let neighbors = self
+            .nearest_cards(embedding, &[], None, limit.map(|l| l as i64), options)
+            .await?;
+        Ok(neighbors
+            .into_iter()
+            .map(|neighbor| neighbor.card)
+            .collect())
+    }
+
+    /// Combines full-text matches with embedding neighbors of the query using
+    /// weighted reciprocal rank fusion
+    pub async fn hybrid_search(
+        &self,
+        query: &str,
+        limit: Option<i32>,
+        lexical_weight: Option<f64>,
+        vector_weight: Option<f64>,
+        rank_constant: Option<f64>,
+        options: &VectorSearchOptions,
+    ) -> Result<Vec<HybridMatch>, Error> {
+        let limit = limit.unwrap_or(10).max(0) as usize;
+        let lexical_weight = lexical_weight.unwrap_or(1.0);
+        let vector_weight = vector_weight.unwrap_or(1.0);
+        let rank_constant = rank_constant.unwrap_or(fusion::DEFAULT_RRF_K);
+        if !(rank_constant > 0.0 && rank_constant.is_finite()) {
+            return Err(Error::BadRequest(format!(
+                "rank_constant must be greater than 0, got {}",
+                rank_constant
+            )));
+        }
+        for (name, weight) in [
+            ("lexical_weight", lexical_weight),
+            ("vector_weight", vector_weight),
+        ] {
+            if !(weight >= 0.0 && weight.is_finite()) {
+                return Err(Error::BadRequest(format!(
+                    "{} must be at least 0, got {}",
+                    name, weight
+                )));
+            }
+        }
+        // Fuse over a deeper pool than requested so cards ranked moderately in
+        // both lists can surface
+        let pool_size = (limit * 4).max(20) as i64;
+
+        let lexical = if lexical_weight > 0.0 {
+            self.repository.lexical_search(query, pool_size).await?
+        } else {
+            Vec::new()
+        };
+        let vector = if vector_weight > 0.0 {
+            let embedding = self.embed_query(query, options.space.as_deref()).await?;
+            self.nearest_cards(embedding, &[], None, Some(pool_size), options)
+                .await?
+                .into_iter()
+                .map(|neighbor| neighbor.card)
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        let lexical_ids: Vec<i32> = lexical.iter().map(|card| card.id).collect();
+        let vector_ids: Vec<i32> = vector.iter().map(|card| card.id).collect();
+        let fused = fusion::reciprocal_rank_fusion(
+            &[
+                RankedList {
+                    items: &lexical_ids,
+                    weight: lexical_weight,
+                },
+                RankedList {
+                    items: &vector_ids,
+                    weight: vector_weight,
+                },
+            ],
+            rank_constant,
+        );
+
+        let mut cards: HashMap<i32, CardModel> = lexical
+            .into_iter()
+            .chain(vector)
+            .map(|card| (card.id, card))
+            .collect();
+
+        Ok(fused
+            .into_iter()
+            .take(limit)
+            .filter_map(|fused| {
+                Some(HybridMatch {
+                    card: cards.remove(&fused.item)?,
+                    score: fused.score,
+                    lexical_rank: fused.ranks[0],
+                    vector_rank: fused.ranks[1],
+                })
+            })
+            .collect())
+    }
+
+    /// Cards closest to the given card in embedding space that satisfy the
+    /// format, color identity and Reserved List constraints
+    pub async fn suggest_replacements(
+        &self,
+        card_name: &str,
+        constraints: &ReplacementConstraints,
+        limit: i64,
+    ) -> Result<Replacements, Error> {
+        let card = self.repository.get_by_name(card_name).await?;
+        let reasons = Self::constraint_violations(&card, constraints);
+
+        let filters = SearchFilters {
+            card_type: constraints
+                .same_card_type
+                .then(|| card.main_type.as_str().to_string()),
+            legal_in: constraints.format.clone(),
+            color_identity_within: constraints.color_identity.clone(),
+            exclude_reserved: Some(constraints.exclude_reserved),
+            ..Default::default()
+        };
+        let query = SimilarCardsQuery {
+            card_names: vec![card.name.clone()],
+            limit,
+            ..Default::default()
+        };
+        let suggestions = self.find_similar_cards(&query, Some(&filters)).await?;
+
+        Ok(Replacements {
+            card,
+            reasons,
+            suggestions,
+        })
+    }
+
+    /// Describes how a card fails the replacement constraints
+    fn constraint_violations(
+        card: &CardModel,
+        constraints: &ReplacementConstraints,
+    ) -> Vec<String> {
+        let mut reasons = Vec::new();
+
+        if let Some(format) = &constraints.format {
+            let format = format.trim().to_lowercase();
+            let status = card
+                .legalities
+                .as_ref()
+                .and_then(|legalities| legalities.get(&format))
+                .and_then(|status| status.as_str())
+                .unwrap_or("not_legal");
+            match status {
+                "legal" | "restricted" => {}
+                "banned" => reasons.push(format!("banned in {}", format)),
+                _ => reasons.push(format!("not legal in {}", format)),
+            }
+        }
+
+        if let Some(colors) = &constraints.color_identity {
+            let allowed: Vec<String> = colors.iter().map(|c| c.trim().to_uppercase()).collect();
+            let outside: Vec<&String> = card
+                .color_identity
+                .iter()
+                .flatten()
+                .filter(|color| !allowed.contains(&color.to_uppercase()))
+                .collect();
+            if !outside.is_empty() {
+                reasons.push(format!(
+                    "color identity includes {} outside the deck's colors",
+                    outside
+                        .iter()
+                        .map(|color| color.as_str())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ));
+            }
+        }
+
+        if constraints.exclude_reserved && card.reserved == Some(true) {
+            reasons.push("on the Reserved List".to_string());
+        }
+
+        reasons
+    }
+
+    /// Compares two cards by embedding similarity and shared attributes
+    pub async fn compare_cards(
+        &self,
+        first_name: &str,
+        second_name: &str,
+    ) -> Result<CardComparison, Error> {
+        let mut first = self.repository.get_by_name(first_name).await?;
+        let mut second = self.repository.get_by_name(second_name).await?;
+        self.fill_embedding(&mut first);
+        self.fill_embedding(&mut second);
+        let explanation = comparison::explain_similarity(&first, &second);
+
+        Ok(CardComparison {
+            first,
+            second,
+            explanation,
+        })
+    }
+
+    /// Runs [`Self::find_similar_cards`] for every query card of a golden set
+    /// and scores the top `k` results against the expected cards. Query cards
+    /// that are missing or have no embedding in the searched space are skipped.
+    pub async fn evaluate_similarity(
+        &self,
+        golden: &GoldenSet,
+        k: usize,
+        vector_options: &VectorSearchOptions,
+    ) -> Result<EvaluationReport, Error> {
+        if k == 0 {
+            return Err(Error::BadRequest("k must be greater than zero".to_string()));
+        }
+
+        let mut evaluations = Vec::new();
+        let mut skipped = BTreeMap::new();
+        for (card, relevant) in &golden.queries {
+            let query = SimilarCardsQuery {
+                card_names: vec![card.clone()],
+                vector_options: vector_options.clone(),
+                limit: k as i64,
+                ..Default::default()
+            };
+            match self.find_similar_cards(&query, None).await {
+                Ok(matches) => {
+                    let ranked: Vec<String> = matches.into_iter().map(|m| m.card.name).collect();
+                    evaluations.push(evaluation::evaluate_ranking(card, &ranked, relevant, k));
+                }
+                Err(Error::NotFound(reason)) | Err(Error::BadRequest(reason)) => {
+                    tracing::warn!("Skipping golden query {}: {}", card, reason);
+                    skipped.insert(card.clone(), reason);
+                }
+                Err(e) => return Err(e),
+            }
+        }
+
+        Ok(EvaluationReport::new(k, evaluations, skipped))
+    }
+
+    /// Builds the k-nearest-neighbor graph over the embedded cards matching the
+    /// filters: only the first `max_cards` matching cards (by ID) become nodes,
+    /// and each links to its `k` nearest neighbors among them.
+    pub async fn knn_graph(
+        &self,
+        filters: &SearchFilters,
+        k: i64,
+        max_cards: i64,
+        metric: DistanceMetric,
+    ) -> Result<KnnGraph, Error> {
+        if k <= 0 {
+            return Err(Error::BadRequest("k must be greater than zero".to_string()));
+        }
+        if k > MAX_GRAPH_NEIGHBORS {
+            return Err(Error::BadRequest(format!(
+                "k must be at most {}",
+                MAX_GRAPH_NEIGHBORS
+            )));
+        }
+        Self::check_max_cards(max_cards, MAX_GRAPH_CARDS)?;
+
+        let nodes = self
+            .repository
+            .knn_nodes(&Self::card_filters(filters), max_cards)
+            .await?;
+
+        // Neighbors are searched among the graph's own cards only. HNSW applies
+        // that filter after its candidate search, so ef_search must exceed k.
+        let ids: Vec<i32> = nodes.iter().map(|card| card.id).collect();
+        let ef_search = (k as u32 + 1).max(DEFAULT_GRAPH_EF_SEARCH);
+        let edges = match &self.memory_index {
+            Some(index) => {
+                let index = index.clone();
+                tokio::task::spawn_blocking(move || {
+                    let allowed: HashSet<i32> = ids.iter().copied().collect();
+                    ids.iter()
+                        .flat_map(|&source| {
+                            let neighbors = match index.vector(source) {
+                                Some(query) => index.search(
+                                    query,
+                                    k as usize,
+                                    metric,
+                                    Some(ef_search as usize),
+                                    &|id| id != source && allowed.contains(&id),
+                                ),
+                                None => Vec::new(),
+                            };
+                            neighbors.into_iter().map(move |neighbor| KnnEdge {
+                                source,
+                                target: neighbor.id,
+                                similarity: metric.similarity(neighbor.distance),
+                            })
+                        })
+                        .collect()
+                })
+                .await
+                .map_err(|e| {
+                    tracing::error!("Failed to search kNN graph neighbors: {:?}", e);
+                    Error::InternalServerError
+                })?
+            }
+            None => {
+                self.repository
+                    .knn_edges(&ids, k, metric, ef_search)
+                    .await?
+            }
+        };
+
+        Ok(KnnGraph {
+            k,
+            metric: metric.as_str().to_string(),
+            nodes: nodes.iter().map(KnnNode::from).collect(),
+            edges,
+        })
+    }
+
+    /// Projects the embeddings of the cards matching the filters onto their
+    /// first two principal components, for plotting a format's card space.
+    /// Only the first `max_cards` matching cards (by ID) are projected.
+    pub async fn project_cards(
+        &self,
+        filters: &SearchFilters,
+        max_cards: i64,
+    ) -> Result<CardProjection, Error> {
+        Self::check_max_cards(max_cards, MAX_ANALYZED_CARDS)?;
+        let mut cards = self
+            .repository
+            .search_with_embeddings(Some(Self::card_filters(filters)), max_cards)
+            .await?;
+        cards.iter_mut().for_each(|card| self.fill_embedding(card));
+        cards.retain(|card| card.embedding.is_some());
+
+        let embeddings: Vec<Vec<f32>> = cards
+            .iter()
+            .map(|card| {
+                card.embedding
+                    .as_ref()
+                    .map(Vector::to_vec)
+                    .unwrap_or_default()
+            })
+            .collect();
+        let projection = tokio::task::spawn_blocking(move || {
+            let slices: Vec<&[f32]> = embeddings.iter().map(|v| v.as_slice()).collect();
+            pca::project_2d(&slices, 100)
+        })
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to project card embeddings: {:?}", e);
+            Error::InternalServerError
+        })?;
+
+        Ok(CardProjection {
+            cards: cards.into_iter().zip(projection.coordinates).collect(),
+            explained_variance: projection.explained_variance,
+        })
+    }
+
+    /// Groups the embedded cards matching the filters into `k` clusters with
+    /// spherical k-means, largest cluster first. Only the first `max_cards`
+    /// matching cards (by ID) are clustered.
+    pub async fn cluster_cards(
+        &self,
+        filters: &SearchFilters,
+        k: usize,
+        max_cards: i64,
+        seed: u64,
+    ) -> Result<Vec<CardCluster>, Error> {
+        if k == 0 {
+            return Err(Error::BadRequest(
+                "Number of clusters must be greater than zero".to_string(),
+            ));
+        }
+        Self::check_max_cards(max_cards, MAX_ANALYZED_CARDS)?;
+
+        let mut cards = self
+            .repository
+            .search_with_embeddings(Some(Self::card_filters(filters)), max_cards)
+            .await?;
+        cards.iter_mut().for_each(|card| self.fill_embedding(card));
+        cards.retain(|card| card.embedding.is_some());
+        let embeddings: Vec<Vec<f32>> = cards
+            .iter()
+            .map(|card| {
+                card.embedding
+                    .as_ref()
+                    .map(Vector::to_vec)
+                    .unwrap_or_default()
+            })
+            .collect();
+        let (embeddings, clustering) = tokio::task::spawn_blocking(move || {
+            let slices: Vec<&[f32]> = embeddings.iter().map(|v| v.as_slice()).collect();
+            let clustering = kmeans::spherical_kmeans(&slices, k, 50, seed);
+            (embeddings, clustering)
+        })
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to cluster card embeddings: {:?}", e);
+            Error::InternalServerError
+        })?;
+
+        let mut members: Vec<Vec<SimilarCard>> =
+            clustering.centroids.iter().map(|_| Vec::new()).collect();
+        for ((card, embedding), cluster) in cards
+            .into_iter()
+            .zip(&embeddings)
+            .zip(clustering.assignments)
+        {
+            let similarity = vector::cosine_similarity(embedding, &clustering.centroids[cluster]);
+            members[cluster].push(SimilarCard {
+                card,
+                similarity: similarity as f64,
+            });
+        }
+
+        let mut clusters: Vec<CardCluster> = members
+            .into_iter()
+            .filter(|cards| !cards.is_empty())
+            .map(|mut cards| {
+                cards.sort_by(|a, b| b.similarity.total_cmp(&a.similarity));
+
+                let mut keyword_counts: HashMap<&str, usize> = HashMap::new();
+                for member in &cards {
+                    for keyword in member.card.keywords.iter().flatten() {
+                        *keyword_counts.entry(keyword.as_str()).or_default() += 1;
+                    }
+                }
+                let mut keywords: Vec<(String, usize)> = keyword_counts
+                    .into_iter()
+                    .map(|(keyword, count)| (keyword.to_string(), count))
+                    .collect();
+                keywords.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+
+                CardCluster { cards, keywords }
+            })
+            .collect();
+        clusters.sort_by_key(|cluster| std::cmp::Reverse(cluster.cards.len()));
+
+        Ok(clusters)
+    }
+
+    /// Scans the whole card pool for pairs of cards whose embeddings have a
+    /// cosine similarity of at least `threshold`, among each card's
+    /// `max_neighbors` nearest neighbors, and groups them into families. The
+    /// report replaces the cached one returned by [`Self::near_duplicates`].
+    /// Only one scan runs at a time; concurrent calls wait for it to finish.
+    pub async fn find_near_duplicates(
+        &self,
+        threshold: f64,
+        max_neighbors: i64,
+    ) -> Result<Arc<DuplicateReport>, Error> {
+        self.ensure_pgvector("Near-duplicate detection")?;
+        if !(0.0..=1.0).contains(&threshold) {
+            return Err(Error::BadRequest(format!(
+                "threshold must be between 0 and 1, got {}",
+                threshold
+            )));
+        }
+        if max_neighbors <= 0 {
+            return Err(Error::BadRequest(
+                "max_neighbors must be greater than zero".to_string(),
+            ));
+        }
+        let _scan = self.duplicate_scan.lock().await;
+
+        let mut seen = HashSet::new();
+        let mut pairs: Vec<SimilarPair> = Vec::new();
+        if let Some((min_id, max_id)) = self.repository.card_id_range().await? {
+            let mut after_id = min_id.saturating_sub(1);
+            while after_id < max_id {
+                let up_to_id = after_id.saturating_add(DUPLICATE_BATCH_SIZE);
+                let batch = self
+                    .repository
+                    .near_duplicate_pairs(threshold, max_neighbors, after_id, up_to_id)
+                    .await?;
+                for pair in batch {
+                    let key = (
+                        pair.first_id.min(pair.second_id),
+                        pair.first_id.max(pair.second_id),
+                    );
+                    if seen.insert(key) {
+                        pairs.push(pair);
+                    }
+                }
+                tracing::debug!(
+                    "Near-duplicate scan at card {} of {}: {} pairs",
+                    up_to_id.min(max_id),
+                    max_id,
+                    pairs.len()
+                );
+                after_id = up_to_id;
+            }
+        }
+
+        let report = Arc::new(DuplicateReport {
+            threshold,
+            max_neighbors,
+            pair_count: pairs.len(),
+            families: duplicates::group_families(pairs),
+        });
+        *self.duplicates.write().await = Some(report.clone());
+        Ok(report)
+    }
+
+    /// The latest near-duplicate report, if a scan has been run
+    pub async fn near_duplicates(&self) -> Option<Arc<DuplicateReport>> {
+        self.duplicates.read().await.clone()
+    }
+
+    /// Computes embeddings for the cards selected by `options.mode` with the
+    /// configured embedder, one batch per transaction so an interrupted run
+    /// keeps its progress. With `options.named_space` the vectors are stored in
+    /// the embedder's named embedding space instead of `card.embedding`. `on_progress` is called after every batch.
+    pub async fn backfill_embeddings(
+        &self,
+        options: &BackfillOptions,
+        mut on_progress: impl FnMut(&BackfillProgress),
+    ) -> Result<BackfillProgress, Error> {
+        if options.batch_size <= 0 {
+            return Err(Error::BadRequest(
+                "batch_size must be greater than zero".to_string(),
+            ));
+        }
+        // Backfilled vectors are written as pgvector values and would also be
+        // missing from the in-memory index
+        self.ensure_pgvector("Embedding backfill")?;
+
+        let embedder = self.embedder()?.clone();
+        let dimension = embedder.dimension();
+        let space = if options.named_space {
+            Some(
+                self.repository
+                    .ensure_embedding_space(embedder.model_id(), dimension as i32)
+                    .await?,
+            )
+        } else {
+            None
+        };
+        let space_id = space.as_ref().map(|space| space.model_id.as_str());
+        let total = self
+            .repository
+            .count_cards_to_embed(options.mode, dimension, space_id, options.after_id)
+            .await?;
+        let mut progress = BackfillProgress {
+            total: options.max_cards.map_or(total, |max| total.min(max)),
+            last_id: options.after_id,
+            ..Default::default()
+        };
+
+        loop {
+            let remaining = options
+                .max_cards
+                .map_or(options.batch_size, |max| max - progress.processed);
+            let batch_size = options.batch_size.min(remaining);
+            if batch_size <= 0 {
+                break;
+            }
+
+            let cards = self
+                .repository
+                .cards_to_embed(
+                    options.mode,
+                    dimension,
+                    space_id,
+                    progress.last_id,
+                    batch_size,
+                )
+                .await?;
+            let Some(last) = cards.last() else {
+                progress.done = true;
+                break;
+            };
+            progress.last_id = Some(last.id);
+            progress.processed += cards.len() as i64;
+
+            let mut embeddings = Vec::with_capacity(cards.len());
+            for card in &cards {
+                match embedder.embed(&backfill::embedding_text(card)) {
+                    Ok(embedding) => embeddings.push((card.id, Vector::from(embedding))),
+                    Err(e) => {
+                        tracing::warn!("Could not embed card {} ({}): {}", card.name, card.id, e);
+                        progress.failed += 1;
+                    }
+                }
+            }
+            progress.updated += embeddings.len() as i64;
+            self.repository
+                .update_embeddings(space.as_ref(), embeddings)
+                .await?;
+            if space.is_none() && progress.updated > 0 {
+                // The near-duplicate report was computed from the old vectors
+                *self.duplicates.write().await = None;
+            }
+
+            on_progress(&progress);
+            if (cards.len() as i64) < batch_size {
+                progress.done = true;
+                break;
+            }
+        }
+
+        Ok(progress)
+    }
+
+    pub async fn create_vector_index(
+        &self,
+        method: VectorIndexMethod,
+        metric: DistanceMetric,
+    ) -> Result<String, Error> {
+        self.ensure_pgvector("Vector indexes")?;
+        let valid = match method {
+            VectorIndexMethod::Hnsw { m, ef_construction } => {
+                (2..=100).contains(&m) && ef_construction >= 2 * m
+            }
+            VectorIndexMethod::IvfFlat { lists } => lists > 0,
+        };
+        if !valid {
+            return Err(Error::BadRequest(
+                "Invalid index parameters: HNSW needs m between 2 and 100 and ef_construction of at least 2 * m, IVFFlat needs lists > 0".to_string(),
+            ));
+        }
+
+        self.repository.create_vector_index(method, metric).await
+    }
+
+    pub async fn list_vector_indexes(&self) -> Result<Vec<VectorIndex>, Error> {
+        self.ensure_pgvector("Vector indexes")?;
+        self.repository.list_vector_indexes().await
+    }
+
+    pub async fn drop_vector_index(&self, name: &str) -> Result<(), Error> {
+        self.ensure_pgvector("Vector indexes")?;
+        self.repository.drop_vector_index(name).await
+    }
+
+    pub async fn find_strictly_better_cards(
+        &self,
+        card_name: &str,
+        limit: Option<i32>,
+    ) -> Result<BetterCards, Error> {
+        let limit = limit.unwrap_or(10).max(0) as usize;
+        let target = self.repository.get_by_name(card_name).await?;
+        let candidates = self.repository.find_comparable_candidates(&target).await?;
+
+        let mut result = BetterCards::default();
+        for candidate in candidates {
+            match comparison::compare(&target, &candidate) {
+                Some(Comparison::StrictlyBetter(reasons))
+                    if result.strictly_better.len() < limit =>
+                {
+                    result.strictly_better.push((candidate, reasons));
+                }
+                Some(Comparison::FunctionalReprint) if result.functional_reprints.len() < limit => {
+                    result.functional_reprints.push(candidate);
+                }
+                _ => {}
+            }
+        }
+
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::embeddings::HashEmbedder;
+    use crate::similarity::ann::BruteForceIndex;
+
+    /// A service serving vector queries in memory, whose database is never
+    /// reached
+    fn memory_service() -> CardService {
+        let pool = sqlx::PgPool::connect_lazy("postgres://localhost/unreachable").unwrap();
+        let index = BruteForceIndex::new(2, vec![(1, vec![1.0, 0.0]), (2, vec![0.0, 1.0])]);
+        CardService::new(CardRepository::new(pool))
+            .with_embedder(Arc::new(HashEmbedder::new(2)))
+            .with_memory_index(Arc::new(index))
+    }
+
+    #[tokio::test]
+    async fn backfill_is_rejected_in_memory_mode() {
+        let service = memory_service();
+        let options = BackfillOptions {
+            mode: Default::default(),
+            batch_size: 10,
+            after_id: None,
+            max_cards: None,
+            named_space: false,
+        };
+        let mut progress_reports = 0;
+        let result = service
+            .backfill_embeddings(&options, |_| progress_reports += 1)
+            .await;
+
+        assert!(
+            matches!(&result, Err(Error::BadRequest(message)) if message.starts_with("Embedding backfill needs the vector extension")),
+            "{:?}",
+            result
+        );
+        assert_eq!(progress_reports, 0);
     }
 }
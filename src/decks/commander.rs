@@ -0,0 +1,787 @@
+use super::model::{Deck, DeckEntry, DeckSection};
+use super::validation::{self, DeckValidation, Violation, ViolationKind};
+use crate::cards::CardModel;
+use std::borrow::Cow;
+use std::collections::HashSet;
+
+/// Which cards may lead a deck in a commander format
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommanderKind {
+    /// Legendary creatures, or cards that say they can be your commander
+    Legendary,
+    /// Also legendary planeswalkers, as in Brawl
+    LegendaryOrPlaneswalker,
+    /// Any creature legal or restricted in the format, as in Pauper
+    /// Commander, where uncommons are restricted to being the commander
+    Creature,
+    /// A planeswalker, plus an optional instant or sorcery signature spell
+    Oathbreaker,
+}
+
+/// How a card lets a second commander join it, read from its keywords, with
+/// partner names and variants taken from its oracle text
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Pairing {
+    /// Plain "Partner"
+    Partner,
+    /// "Partner with <name>"
+    PartnerWith(String),
+    /// A partner variant such as "Partner—Survivors", which only pairs with
+    /// the same variant
+    PartnerVariant(String),
+    FriendsForever,
+    ChooseABackground,
+    DoctorsCompanion,
+}
+
+/// Type line of a card's front face
+fn front_type_line(card: &CardModel) -> &str {
+    let type_line = card.type_line.as_deref().unwrap_or_default();
+    type_line
+        .split_once(" // ")
+        .map_or(type_line, |(front, _)| front)
+}
+
+fn is_background(card: &CardModel) -> bool {
+    front_type_line(card).contains("Background")
+}
+
+fn is_time_lord_doctor(card: &CardModel) -> bool {
+    let type_line = front_type_line(card);
+    type_line.contains("Creature") && type_line.contains("Time Lord Doctor")
+}
+
+/// Pairing abilities of a card. Keywords say which abilities it has; the
+/// partner's name or variant comes from the ability's line of oracle text,
+/// ignoring reminder text.
+fn pairings(card: &CardModel) -> Vec<Pairing> {
+    let keywords: Vec<String> = card
+        .keywords
+        .iter()
+        .flatten()
+        .map(|keyword| keyword.to_lowercase().replace('’', "'"))
+        .collect();
+    let has = |keyword: &str| keywords.iter().any(|k| k == keyword);
+
+    let mut pairings = Vec::new();
+    if keywords
+        .iter()
+        .any(|keyword| keyword.starts_with("partner"))
+    {
+        let abilities = card
+            .oracle_text
+            .as_deref()
+            .unwrap_or_default()
+            .lines()
+            .map(|line| line.split(" (").next().unwrap_or_default().trim());
+        for ability in abilities {
+            let pairing = if ability == "Partner" {
+                Pairing::Partner
+            } else if let Some(name) = ability.strip_prefix("Partner with ") {
+                Pairing::PartnerWith(name.to_lowercase())
+            } else if let Some(variant) = ability
+                .strip_prefix("Partner—")
+                .or_else(|| ability.strip_prefix("Partner — "))
+            {
+                Pairing::PartnerVariant(variant.to_lowercase())
+            } else {
+                continue;
+            };
+            pairings.push(pairing);
+        }
+    }
+    if has("friends forever") {
+        pairings.push(Pairing::FriendsForever);
+    }
+    if has("choose a background") {
+        pairings.push(Pairing::ChooseABackground);
+    }
+    if has("doctor's companion") {
+        pairings.push(Pairing::DoctorsCompanion);
+    }
+    pairings
+}
+
+/// Whether two cards may be commanders together
+fn can_pair(first: &CardModel, second: &CardModel) -> bool {
+    let first_pairings = pairings(first);
+    let second_pairings = pairings(second);
+    let pairs_with = |pairings: &[Pairing], card: &CardModel, other: &[Pairing]| {
+        pairings.iter().any(|pairing| match pairing {
+            Pairing::Partner => other.contains(&Pairing::Partner),
+            Pairing::PartnerWith(name) => *name == card.name.to_lowercase(),
+            Pairing::PartnerVariant(_) | Pairing::FriendsForever => other.contains(pairing),
+            Pairing::ChooseABackground => is_background(card),
+            Pairing::DoctorsCompanion => is_time_lord_doctor(card),
+        })
+    };
+    pairs_with(&first_pairings, second, &second_pairings)
+        || pairs_with(&second_pairings, first, &first_pairings)
+}
+
+/// Why a card cannot be a commander on its own, if it cannot
+fn ineligibility(card: &CardModel, kind: CommanderKind, format: &str) -> Option<String> {
+    let type_line = front_type_line(card);
+    let legendary = type_line.contains("Legendary");
+    let creature = type_line.contains("Creature");
+    let planeswalker = type_line.contains("Planeswalker");
+    let says_commander = card
+        .oracle_text
+        .as_deref()
+        .is_some_and(|text| text.contains("can be your commander"));
+    let eligible = match kind {
+        CommanderKind::Legendary => (legendary && creature) || says_commander,
+        CommanderKind::LegendaryOrPlaneswalker => {
+            (legendary && (creature || planeswalker)) || says_commander
+        }
+        CommanderKind::Creature => {
+            (creature || says_commander)
+                && matches!(
+                    validation::legality(card, format),
+                    Some("legal" | "restricted")
+                )
+        }
+        CommanderKind::Oathbreaker => planeswalker,
+    };
+    if eligible {
+        return None;
+    }
+    Some(match kind {
+        CommanderKind::Legendary => format!(
+            "{} cannot be a commander: it is not a legendary creature",
+            card.name
+        ),
+        CommanderKind::LegendaryOrPlaneswalker => format!(
+            "{} cannot be a commander: it is not a legendary creature or planeswalker",
+            card.name
+        ),
+        CommanderKind::Creature if creature || says_commander => format!(
+            "{} cannot be a commander: it is neither legal nor restricted in {}",
+            card.name, format
+        ),
+        CommanderKind::Creature => {
+            format!("{} cannot be a commander: it is not a creature", card.name)
+        }
+        CommanderKind::Oathbreaker => format!(
+            "{} cannot be an oathbreaker: it is not a planeswalker",
+            card.name
+        ),
+    })
+}
+
+fn is_signature_spell(card: &CardModel) -> bool {
+    let type_line = front_type_line(card);
+    type_line.contains("Instant") || type_line.contains("Sorcery")
+}
+
+fn commander_violation(validation: &mut DeckValidation, card: &CardModel, message: String) {
+    validation.add_card_violation(
+        &card.name,
+        1,
+        Violation {
+            kind: ViolationKind::InvalidCommander,
+            message,
+        },
+    );
+}
+
+/// The deck with its sideboard read as its commanders when it has no
+/// Commander section and only one or two cards in the sideboard, as MTGO
+/// `.dek` files and plain text exports list the commander. A companion also
+/// listed in the sideboard stays there.
+pub fn with_sideboard_commanders(deck: &Deck) -> Cow<'_, Deck> {
+    let sideboard = deck.count(DeckSection::Sideboard);
+    let has_companion = deck.section(DeckSection::Sideboard).any(|entry| {
+        deck.section(DeckSection::Companion)
+            .any(|companion| companion.card.id == entry.card.id)
+    });
+    if deck.section(DeckSection::Commander).next().is_some()
+        || !(1..=2).contains(&sideboard)
+        || has_companion
+    {
+        return Cow::Borrowed(deck);
+    }
+
+    let mut deck = deck.clone();
+    for entry in &mut deck.entries {
+        if entry.line.section == DeckSection::Sideboard {
+            entry.line.section = DeckSection::Commander;
+        }
+    }
+    Cow::Owned(deck)
+}
+
+/// Checks the commanders of a deck and that every card fits their combined
+/// color identity. `entries` are the entries that are part of the deck.
+pub fn validate_commanders(
+    entries: &[&DeckEntry],
+    kind: CommanderKind,
+    validation: &mut DeckValidation,
+) {
+    let listed: Vec<&CardModel> = entries
+        .iter()
+        .filter(|entry| entry.line.section == DeckSection::Commander)
+        .map(|entry| &entry.card)
+        .collect();
+
+    // An oathbreaker's signature spell is listed with it, but is not a commander
+    let (commanders, signature_spells): (Vec<&CardModel>, Vec<&CardModel>) =
+        if kind == CommanderKind::Oathbreaker {
+            listed
+                .iter()
+                .copied()
+                .partition(|card| !is_signature_spell(card) || listed.len() == 1)
+        } else {
+            (listed, Vec::new())
+        };
+
+    match commanders.as_slice() {
+        [] => validation.add_deck_violation(Violation {
+            kind: ViolationKind::MissingCommander,
+            message: format!(
+                "The deck has no commander, required in {}",
+                validation.format
+            ),
+        }),
+        [commander] => {
+            if let Some(message) = ineligibility(commander, kind, &validation.format) {
+                commander_violation(validation, commander, message);
+            }
+        }
+        [first, second] if kind != CommanderKind::Oathbreaker => {
+            for (card, other) in [(first, second), (second, first)] {
+                let background_of_other =
+                    is_background(card) && pairings(other).contains(&Pairing::ChooseABackground);
+                if background_of_other {
+                    continue;
+                }
+                if let Some(message) = ineligibility(card, kind, &validation.format) {
+                    commander_violation(validation, card, message);
+                }
+            }
+            if !can_pair(first, second) {
+                validation.add_deck_violation(Violation {
+                    kind: ViolationKind::InvalidCommanderPair,
+                    message: format!(
+                        "{} and {} cannot be commanders together: they need partner, friends forever, a background or doctor's companion to pair",
+                        first.name, second.name
+                    ),
+                });
+            }
+        }
+        commanders => validation.add_deck_violation(Violation {
+            kind: ViolationKind::TooManyCommanders,
+            message: format!(
+                "The deck has {} commanders, at most {} allowed",
+                commanders.len(),
+                if kind == CommanderKind::Oathbreaker {
+                    1
+                } else {
+                    2
+                }
+            ),
+        }),
+    }
+    if signature_spells.len() > 1 {
+        validation.add_deck_violation(Violation {
+            kind: ViolationKind::TooManyCommanders,
+            message: format!(
+                "The deck has {} signature spells, at most 1 allowed",
+                signature_spells.len()
+            ),
+        });
+    }
+
+    // Cards restricted in Pauper Commander may only be the commander
+    if kind == CommanderKind::Creature {
+        let mut checked = HashSet::new();
+        for entry in entries {
+            let card = &entry.card;
+            let restricted = validation::legality(card, &validation.format) == Some("restricted");
+            if entry.line.section != DeckSection::Commander && restricted && checked.insert(card.id)
+            {
+                validation.add_card_violation(
+                    &card.name,
+                    entry.line.quantity,
+                    Violation {
+                        kind: ViolationKind::Restricted,
+                        message: format!(
+                            "{} is restricted in {}: it may only be the commander",
+                            card.name, validation.format
+                        ),
+                    },
+                );
+            }
+        }
+    }
+
+    let mut color_identity: Vec<String> = commanders
+        .iter()
+        .flat_map(|card| card.color_identity.iter().flatten().cloned())
+        .collect();
+    color_identity.sort();
+    color_identity.dedup();
+
+    let mut checked = HashSet::new();
+    for entry in entries {
+        let card = &entry.card;
+        if commanders.is_empty() || !checked.insert(card.id) {
+            continue;
+        }
+        let outside: Vec<&str> = card
+            .color_identity
+            .iter()
+            .flatten()
+            .filter(|color| !color_identity.contains(color))
+            .map(String::as_str)
+            .collect();
+        if !outside.is_empty() {
+            validation.add_card_violation(
+                &card.name,
+                entry.line.quantity,
+                Violation {
+                    kind: ViolationKind::OutsideColorIdentity,
+                    message: format!(
+                        "{} has {} outside the commander's color identity ({})",
+                        card.name,
+                        outside.join(""),
+                        if color_identity.is_empty() {
+                            "colorless".to_string()
+                        } else {
+                            color_identity.join("")
+                        }
+                    ),
+                },
+            );
+        }
+    }
+
+    validation.commanders = commanders.iter().map(|card| card.name.clone()).collect();
+    validation.color_identity = (!commanders.is_empty()).then_some(color_identity);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::validation::tests::{card, deck, entry, with_legality};
+    use super::super::validation::{validate, FormatRules};
+    use super::*;
+
+    fn commander(
+        id: i32,
+        name: &str,
+        type_line: &str,
+        oracle_text: &str,
+        keywords: &[&str],
+    ) -> CardModel {
+        let mut card = card(id, name, type_line, oracle_text);
+        card.keywords = Some(keywords.iter().map(|keyword| keyword.to_string()).collect());
+        card
+    }
+
+    fn with_colors(mut card: CardModel, colors: &[&str]) -> CardModel {
+        card.color_identity = Some(colors.iter().map(|color| color.to_string()).collect());
+        card
+    }
+
+    fn mountain() -> CardModel {
+        card(1, "Mountain", "Basic Land — Mountain", "({T}: Add {R}.)")
+    }
+
+    /// Validates the commanders plus enough basic lands for a legal deck size
+    fn validate_with(
+        format: &str,
+        commanders: &[&CardModel],
+        others: &[&CardModel],
+    ) -> DeckValidation {
+        let rules = FormatRules::for_format(format).unwrap();
+        let lands = rules.min_deck_size - (commanders.len() + others.len()) as u32;
+        let mut entries: Vec<DeckEntry> = commanders
+            .iter()
+            .map(|card| entry(card, 1, DeckSection::Commander))
+            .collect();
+        entries.extend(others.iter().map(|card| entry(card, 1, DeckSection::Main)));
+        entries.push(entry(&mountain(), lands, DeckSection::Main));
+        validate(&deck(entries), rules)
+    }
+
+    fn kinds(validation: &DeckValidation) -> Vec<ViolationKind> {
+        validation
+            .deck_violations
+            .iter()
+            .chain(
+                validation
+                    .card_violations
+                    .iter()
+                    .flat_map(|card| &card.violations),
+            )
+            .map(|violation| violation.kind)
+            .collect()
+    }
+
+    fn thrasios() -> CardModel {
+        commander(
+            10,
+            "Thrasios, Triton Hero",
+            "Legendary Creature — Merfolk Wizard",
+            "{4}: Scry 1, then reveal the top card of your library. If it's a land card, put it onto the battlefield tapped. Otherwise, draw a card.\nPartner (You can have two commanders if both have partner.)",
+            &["Partner"],
+        )
+    }
+
+    fn tymna() -> CardModel {
+        commander(
+            11,
+            "Tymna the Weaver",
+            "Legendary Creature — Human Cleric",
+            "Lifelink\nAt the beginning of your postcombat main phase, you may pay X life, where X is the number of opponents that were dealt combat damage this turn. If you do, draw X cards.\nPartner (You can have two commanders if both have partner.)",
+            &["Lifelink", "Partner"],
+        )
+    }
+
+    fn krenko() -> CardModel {
+        commander(
+            12,
+            "Krenko, Mob Boss",
+            "Legendary Creature — Goblin Warrior",
+            "{T}: Create X 1/1 red Goblin creature tokens, where X is the number of Goblins you control.",
+            &[],
+        )
+    }
+
+    #[test]
+    fn single_legendary_commander() {
+        let validation = validate_with("commander", &[&krenko()], &[]);
+        assert!(validation.legal, "{:?}", validation);
+        assert_eq!(validation.commanders, vec!["Krenko, Mob Boss"]);
+        assert_eq!(validation.deck_size, 100);
+    }
+
+    #[test]
+    fn nonlegendary_commander_is_invalid() {
+        let goblin = commander(
+            13,
+            "Goblin Guide",
+            "Creature — Goblin Scout",
+            "Haste",
+            &["Haste"],
+        );
+        assert_eq!(
+            kinds(&validate_with("commander", &[&goblin], &[])),
+            vec![ViolationKind::InvalidCommander]
+        );
+    }
+
+    #[test]
+    fn partners_pair() {
+        assert!(validate_with("commander", &[&thrasios(), &tymna()], &[]).legal);
+        assert_eq!(
+            kinds(&validate_with("commander", &[&thrasios(), &krenko()], &[])),
+            vec![ViolationKind::InvalidCommanderPair]
+        );
+    }
+
+    #[test]
+    fn pairing_comes_from_keywords() {
+        // Oracle text alone, e.g. an ability granting partner, does not pair
+        let mut no_keyword = thrasios();
+        no_keyword.keywords = Some(Vec::new());
+        assert_eq!(
+            kinds(&validate_with("commander", &[&no_keyword, &tymna()], &[])),
+            vec![ViolationKind::InvalidCommanderPair]
+        );
+    }
+
+    #[test]
+    fn partner_with_pairs_only_with_the_named_card() {
+        let pir = commander(
+            14,
+            "Pir, Imaginative Rascal",
+            "Legendary Creature — Human",
+            "Partner with Toothy, Imaginary Friend (When this creature enters, target player may put Toothy into their hand from their library, then shuffle.)\nIf one or more counters would be put on a permanent your team controls, that many plus one of each of those kinds of counters are put on that permanent instead.",
+            &["Partner with"],
+        );
+        let toothy = commander(
+            15,
+            "Toothy, Imaginary Friend",
+            "Legendary Creature — Illusion",
+            "Partner with Pir, Imaginative Rascal (When this creature enters, target player may put Pir into their hand from their library, then shuffle.)\nWhenever you draw a card, put a +1/+1 counter on Toothy.",
+            &["Partner with"],
+        );
+        assert!(validate_with("commander", &[&pir, &toothy], &[]).legal);
+        assert_eq!(
+            kinds(&validate_with("commander", &[&pir, &thrasios()], &[])),
+            vec![ViolationKind::InvalidCommanderPair]
+        );
+    }
+
+    #[test]
+    fn partner_variants_pair_with_the_same_variant() {
+        let survivor = |id, name: &str| {
+            commander(
+                id,
+                name,
+                "Legendary Creature — Human Survivor",
+                "Partner—Survivors (You can have two commanders if both have this ability.)",
+                &["Partner—Survivors"],
+            )
+        };
+        let character_select = commander(
+            18,
+            "Cloud, Midgar Mercenary",
+            "Legendary Creature — Human Soldier Mercenary",
+            "Partner—Character select (You can have two commanders if both have this ability.)",
+            &["Partner—Character select"],
+        );
+        assert!(
+            validate_with(
+                "commander",
+                &[&survivor(16, "Aurelia"), &survivor(17, "Winter")],
+                &[]
+            )
+            .legal
+        );
+        assert_eq!(
+            kinds(&validate_with(
+                "commander",
+                &[&survivor(16, "Aurelia"), &character_select],
+                &[]
+            )),
+            vec![ViolationKind::InvalidCommanderPair]
+        );
+        assert_eq!(
+            kinds(&validate_with(
+                "commander",
+                &[&survivor(16, "Aurelia"), &thrasios()],
+                &[]
+            )),
+            vec![ViolationKind::InvalidCommanderPair]
+        );
+    }
+
+    #[test]
+    fn friends_forever_pair() {
+        let friend = |id, name: &str| {
+            commander(
+                id,
+                name,
+                "Legendary Creature — Human",
+                "Friends forever (You can have two commanders if both have friends forever.)",
+                &["Friends forever"],
+            )
+        };
+        assert!(
+            validate_with(
+                "commander",
+                &[&friend(19, "Will"), &friend(20, "Dustin")],
+                &[]
+            )
+            .legal
+        );
+        assert_eq!(
+            kinds(&validate_with(
+                "commander",
+                &[&friend(19, "Will"), &thrasios()],
+                &[]
+            )),
+            vec![ViolationKind::InvalidCommanderPair]
+        );
+    }
+
+    #[test]
+    fn choose_a_background_pairs_with_a_background() {
+        let wilson = commander(
+            21,
+            "Wilson, Refined Grizzly",
+            "Legendary Creature — Bear Warrior",
+            "Choose a Background (You can have a Background as a second commander.)\nReach, trample, ward {2}",
+            &["Choose a background", "Reach", "Trample", "Ward"],
+        );
+        let background = card(
+            22,
+            "Raised by Giants",
+            "Legendary Enchantment — Background",
+            "Commander creatures you own have base power and toughness 10/10 and are Giants in addition to their other types.",
+        );
+        assert!(validate_with("commander", &[&wilson, &background], &[]).legal);
+        // A Background cannot lead the deck with another commander
+        assert_eq!(
+            kinds(&validate_with("commander", &[&krenko(), &background], &[])),
+            vec![
+                ViolationKind::InvalidCommanderPair,
+                ViolationKind::InvalidCommander
+            ]
+        );
+    }
+
+    #[test]
+    fn doctors_companion_pairs_with_a_time_lord_doctor() {
+        let clara = commander(
+            23,
+            "Clara Oswald",
+            "Legendary Creature — Human",
+            "If a triggered ability of a Doctor you control triggers, that ability triggers an additional time.\nDoctor's companion (You can have two commanders if the other is the Doctor.)",
+            &["Doctor’s companion"],
+        );
+        let doctor = commander(
+            24,
+            "The Eleventh Doctor",
+            "Legendary Creature — Time Lord Doctor",
+            "I. AM. TALKING!",
+            &[],
+        );
+        assert!(validate_with("commander", &[&clara, &doctor], &[]).legal);
+        assert_eq!(
+            kinds(&validate_with("commander", &[&clara, &krenko()], &[])),
+            vec![ViolationKind::InvalidCommanderPair]
+        );
+    }
+
+    #[test]
+    fn too_many_commanders() {
+        assert_eq!(
+            kinds(&validate_with(
+                "commander",
+                &[&thrasios(), &tymna(), &krenko()],
+                &[]
+            )),
+            vec![ViolationKind::TooManyCommanders]
+        );
+    }
+
+    #[test]
+    fn cards_must_fit_the_combined_color_identity() {
+        let thrasios = with_colors(thrasios(), &["G", "U"]);
+        let tymna = with_colors(tymna(), &["W", "B"]);
+        let bolt = with_colors(card(25, "Lightning Bolt", "Instant", ""), &["R"]);
+        let swords = with_colors(card(26, "Swords to Plowshares", "Instant", ""), &["W"]);
+
+        let validation = validate_with("commander", &[&thrasios, &tymna], &[&bolt, &swords]);
+        assert_eq!(
+            kinds(&validation),
+            vec![ViolationKind::OutsideColorIdentity]
+        );
+        assert_eq!(validation.card_violations[0].name, "Lightning Bolt");
+        assert_eq!(
+            validation.color_identity,
+            Some(vec![
+                "B".to_string(),
+                "G".to_string(),
+                "U".to_string(),
+                "W".to_string()
+            ])
+        );
+
+        // Colorless commanders only allow colorless cards
+        let validation = validate_with("commander", &[&krenko()], &[&swords]);
+        assert_eq!(
+            kinds(&validation),
+            vec![ViolationKind::OutsideColorIdentity]
+        );
+        assert_eq!(validation.color_identity, Some(Vec::new()));
+    }
+
+    #[test]
+    fn sideboard_holds_the_commander_without_a_commander_section() {
+        let deck_with = |sideboard: &[&CardModel]| {
+            let mut entries = vec![entry(&mountain(), 99, DeckSection::Main)];
+            entries.extend(
+                sideboard
+                    .iter()
+                    .map(|card| entry(card, 1, DeckSection::Sideboard)),
+            );
+            validate(
+                &deck(entries),
+                FormatRules::for_format("commander").unwrap(),
+            )
+        };
+
+        let validation = deck_with(&[&krenko()]);
+        assert!(validation.legal, "{:?}", validation);
+        assert_eq!(validation.commanders, vec!["Krenko, Mob Boss"]);
+        assert_eq!(validation.deck_size, 100);
+        assert_eq!(validation.sideboard_size, 0);
+
+        let validation = deck_with(&[&thrasios(), &tymna()]);
+        assert_eq!(validation.commanders.len(), 2);
+        assert_eq!(kinds(&validation), vec![ViolationKind::DeckTooLarge]);
+
+        // A larger sideboard is not read as commanders
+        let validation = deck_with(&[&thrasios(), &tymna(), &krenko()]);
+        assert_eq!(
+            kinds(&validation),
+            vec![
+                ViolationKind::MissingCommander,
+                ViolationKind::DeckTooSmall,
+                ViolationKind::SideboardTooLarge
+            ]
+        );
+    }
+
+    #[test]
+    fn pauper_commander_needs_a_legal_or_restricted_creature() {
+        let uncommon = with_legality(
+            commander(
+                27,
+                "Tatyova, Benthic Druid",
+                "Legendary Creature — Merfolk Druid",
+                "",
+                &[],
+            ),
+            "paupercommander",
+            "restricted",
+        );
+        let common = commander(
+            28,
+            "Mulldrifter",
+            "Creature — Elemental",
+            "Flying",
+            &["Flying", "Evoke"],
+        );
+        let rare = with_legality(
+            commander(29, "Grim Lavamancer", "Creature — Human Wizard", "", &[]),
+            "paupercommander",
+            "not_legal",
+        );
+        let instant = card(30, "Counterspell", "Instant", "Counter target spell.");
+
+        assert!(validate_with("paupercommander", &[&uncommon], &[]).legal);
+        assert!(validate_with("paupercommander", &[&common], &[]).legal);
+        assert_eq!(
+            kinds(&validate_with("paupercommander", &[&rare], &[])),
+            vec![ViolationKind::NotLegal, ViolationKind::InvalidCommander]
+        );
+        assert_eq!(
+            kinds(&validate_with("paupercommander", &[&instant], &[])),
+            vec![ViolationKind::InvalidCommander]
+        );
+    }
+
+    #[test]
+    fn pauper_commander_restricted_cards_may_only_be_the_commander() {
+        let uncommon = with_legality(
+            commander(31, "Guardian Project", "Enchantment", "", &[]),
+            "paupercommander",
+            "restricted",
+        );
+        let validation = validate_with("paupercommander", &[&krenko()], &[&uncommon]);
+        assert_eq!(kinds(&validation), vec![ViolationKind::Restricted]);
+        assert_eq!(validation.card_violations[0].name, "Guardian Project");
+    }
+
+    #[test]
+    fn oathbreaker_with_signature_spell() {
+        let oathbreaker = commander(
+            32,
+            "Chandra, Torch of Defiance",
+            "Legendary Planeswalker — Chandra",
+            "",
+            &[],
+        );
+        let spell = card(33, "Lightning Bolt", "Instant", "");
+        assert!(validate_with("oathbreaker", &[&oathbreaker, &spell], &[]).legal);
+        assert_eq!(
+            kinds(&validate_with("oathbreaker", &[&krenko()], &[])),
+            vec![ViolationKind::InvalidCommander]
+        );
+    }
+}
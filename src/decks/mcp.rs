@@ -0,0 +1,57 @@
+use super::model::{DeckLine, DeckParseError, DeckSection, DecklistFormat};
+use schemars;
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct ParseDecklistRequest {
+    /// The decklist: MTG Arena export, Moxfield/Archidekt plain text, MTGO .dek XML or CSV
+    pub decklist: String,
+    /// Format of the decklist: auto, text, dek or csv (default: auto)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub format: Option<DecklistFormat>,
+}
+
+#[derive(Debug, serde::Serialize, schemars::JsonSchema)]
+pub struct DeckEntryOutput {
+    pub quantity: u32,
+    /// Name of the matched card
+    pub name: String,
+    pub card_id: i32,
+    pub section: DeckSection,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub set_code: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub collector_number: Option<String>,
+    /// Line of the decklist the entry was read from
+    pub line_number: usize,
+}
+
+#[derive(Debug, serde::Serialize, schemars::JsonSchema)]
+pub struct SectionCount {
+    pub section: DeckSection,
+    /// Number of cards, counting quantities
+    pub count: u32,
+}
+
+#[derive(Debug, serde::Serialize, schemars::JsonSchema)]
+pub struct ParseDecklistResponse {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Card counts of the non-empty sections
+    pub sections: Vec<SectionCount>,
+    pub entries: Vec<DeckEntryOutput>,
+    /// Lines naming no known card
+    pub unresolved: Vec<DeckLine>,
+    /// Lines that could not be read
+    pub errors: Vec<DeckParseError>,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct ValidateDeckRequest {
+    /// The decklist: MTG Arena export, Moxfield/Archidekt plain text, MTGO .dek XML or CSV
+    pub decklist: String,
+    /// Game format to check against, as named in card legalities (e.g. standard, modern, vintage, pauper, commander)
+    pub format: String,
+    /// Format of the decklist: auto, text, dek or csv (default: auto)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub decklist_format: Option<DecklistFormat>,
+}
@@ -0,0 +1,12 @@
+pub mod commander;
+pub mod mcp;
+mod model;
+pub mod parser;
+pub mod service;
+pub mod validation;
+
+pub use model::{
+    Deck, DeckEntry, DeckLine, DeckParseError, DeckSection, DecklistFormat, ParsedDeck,
+};
+pub use service::DeckService;
+pub use validation::{DeckValidation, FormatRules, Violation, ViolationKind};
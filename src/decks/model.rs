@@ -0,0 +1,143 @@
+use crate::cards::CardModel;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Part of a deck a card is listed in
+#[derive(
+    Debug, Clone, Copy, Default, Deserialize, Serialize, PartialEq, Eq, Hash, schemars::JsonSchema,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum DeckSection {
+    #[default]
+    Main,
+    Sideboard,
+    Commander,
+    Companion,
+    /// Cards being considered, not part of the deck
+    Maybeboard,
+}
+
+impl DeckSection {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DeckSection::Main => "main",
+            DeckSection::Sideboard => "sideboard",
+            DeckSection::Commander => "commander",
+            DeckSection::Companion => "companion",
+            DeckSection::Maybeboard => "maybeboard",
+        }
+    }
+
+    /// Section named by a header line or category such as "Sideboard:",
+    /// "// Commander", "Mainboard" or "Sideboard (15)"
+    pub fn from_header(header: &str) -> Option<Self> {
+        let mut header = header
+            .trim()
+            .trim_start_matches("//")
+            .trim_end_matches(':')
+            .trim();
+        // Drop a trailing card count
+        if let Some((name, count)) = header.rsplit_once(" (") {
+            if count
+                .strip_suffix(')')
+                .is_some_and(|count| count.chars().all(|c| c.is_ascii_digit()))
+            {
+                header = name.trim();
+            }
+        }
+        let header = header.to_lowercase();
+        match header.as_str() {
+            "deck" | "main" | "maindeck" | "main deck" | "mainboard" => Some(DeckSection::Main),
+            "sideboard" | "side" | "sb" => Some(DeckSection::Sideboard),
+            "commander" | "commanders" => Some(DeckSection::Commander),
+            "companion" => Some(DeckSection::Companion),
+            "maybeboard" | "maybe" | "considering" => Some(DeckSection::Maybeboard),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for DeckSection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Decklist file formats
+#[derive(
+    Debug, Clone, Copy, Default, Deserialize, Serialize, PartialEq, Eq, schemars::JsonSchema,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum DecklistFormat {
+    /// Detect the format from the content
+    #[default]
+    Auto,
+    /// MTG Arena export or Moxfield/Archidekt plain text: `4 Lightning Bolt (M10) 146`
+    Text,
+    /// MTGO `.dek` XML
+    Dek,
+    /// CSV with a header row naming the quantity and name columns
+    Csv,
+}
+
+/// One card line of a decklist, before it is matched to a card
+#[derive(Debug, Clone, Serialize, schemars::JsonSchema)]
+pub struct DeckLine {
+    /// 1-based line (or CSV row) the entry was read from
+    pub line_number: usize,
+    pub quantity: u32,
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub set_code: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub collector_number: Option<String>,
+    pub section: DeckSection,
+}
+
+/// A line that could not be read as a card entry
+#[derive(Debug, Clone, Serialize, schemars::JsonSchema)]
+pub struct DeckParseError {
+    pub line_number: usize,
+    pub line: String,
+    pub message: String,
+}
+
+/// A decklist read from text, before its cards are resolved
+#[derive(Debug, Clone, Default)]
+pub struct ParsedDeck {
+    /// Deck name, if the list declares one
+    pub name: Option<String>,
+    pub lines: Vec<DeckLine>,
+    pub errors: Vec<DeckParseError>,
+}
+
+/// A decklist entry matched to a card
+#[derive(Debug, Clone)]
+pub struct DeckEntry {
+    pub line: DeckLine,
+    pub card: CardModel,
+}
+
+/// A decklist whose lines have been resolved against the card database
+#[derive(Debug, Clone, Default)]
+pub struct Deck {
+    pub name: Option<String>,
+    pub entries: Vec<DeckEntry>,
+    /// Lines naming no known card
+    pub unresolved: Vec<DeckLine>,
+    pub errors: Vec<DeckParseError>,
+}
+
+impl Deck {
+    /// Entries in a section
+    pub fn section(&self, section: DeckSection) -> impl Iterator<Item = &DeckEntry> {
+        self.entries
+            .iter()
+            .filter(move |entry| entry.line.section == section)
+    }
+
+    /// Number of cards in a section, counting quantities
+    pub fn count(&self, section: DeckSection) -> u32 {
+        self.section(section).map(|entry| entry.line.quantity).sum()
+    }
+}
@@ -0,0 +1,732 @@
+use super::model::{DeckLine, DeckParseError, DeckSection, DecklistFormat, ParsedDeck};
+
+const CSV_NAME_COLUMNS: &[&str] = &["name", "card name", "card"];
+const CSV_QUANTITY_COLUMNS: &[&str] = &["quantity", "count", "qty", "amount"];
+const CSV_SET_COLUMNS: &[&str] = &["set code", "edition", "set", "edition code"];
+const CSV_NUMBER_COLUMNS: &[&str] = &["collector number", "collector #", "card number", "number"];
+const CSV_SECTION_COLUMNS: &[&str] = &["section", "board", "category", "categories"];
+
+/// Parses a decklist in the given format, detecting it from the content for
+/// [`DecklistFormat::Auto`]. Unreadable lines are reported in
+/// [`ParsedDeck::errors`] rather than failing the whole list.
+pub fn parse(content: &str, format: DecklistFormat) -> ParsedDeck {
+    match format {
+        DecklistFormat::Auto => parse(content, detect_format(content)),
+        DecklistFormat::Text => parse_text(content),
+        DecklistFormat::Dek => parse_dek(content),
+        DecklistFormat::Csv => parse_csv(content),
+    }
+}
+
+/// Guesses the format of a decklist: XML is MTGO `.dek`, a first line with a
+/// name column header is CSV, anything else is plain text
+pub fn detect_format(content: &str) -> DecklistFormat {
+    let trimmed = content.trim_start_matches('\u{feff}').trim_start();
+    if trimmed.starts_with("<?xml") || trimmed.starts_with("<Deck") {
+        return DecklistFormat::Dek;
+    }
+
+    let is_csv_header = trimmed
+        .lines()
+        .next()
+        .map(|line| {
+            let fields = split_csv(line).into_iter().next().unwrap_or_default().1;
+            fields.len() > 1
+                && fields
+                    .iter()
+                    .any(|field| CSV_NAME_COLUMNS.contains(&field.trim().to_lowercase().as_str()))
+        })
+        .unwrap_or(false);
+    if is_csv_header {
+        DecklistFormat::Csv
+    } else {
+        DecklistFormat::Text
+    }
+}
+
+/// MTG Arena exports and Moxfield/Archidekt/MTGO plain text. Sections start
+/// with a header line ("Deck", "Sideboard:", "Commander", ...); without any
+/// header, cards after the first blank line are the sideboard.
+fn parse_text(content: &str) -> ParsedDeck {
+    let mut deck = ParsedDeck::default();
+    let mut section = DeckSection::Main;
+    let mut saw_header = false;
+    let mut in_about = false;
+
+    for (index, raw) in content.lines().enumerate() {
+        let line_number = index + 1;
+        let line = raw.trim().trim_start_matches('\u{feff}');
+
+        if line.is_empty() {
+            let has_main_cards = deck
+                .lines
+                .iter()
+                .any(|line| line.section == DeckSection::Main);
+            if !saw_header && section == DeckSection::Main && has_main_cards {
+                section = DeckSection::Sideboard;
+            }
+            continue;
+        }
+
+        // Arena exports may start with an "About" block naming the deck
+        if line.eq_ignore_ascii_case("about") {
+            in_about = true;
+            continue;
+        }
+        if in_about {
+            if let Some(name) = line.strip_prefix("Name ") {
+                deck.name = Some(name.trim().to_string());
+                continue;
+            }
+        }
+        in_about = false;
+
+        if let Some(header) = DeckSection::from_header(line) {
+            section = header;
+            saw_header = true;
+            continue;
+        }
+        if line.starts_with('#') || line.starts_with("//") {
+            continue;
+        }
+
+        match parse_text_entry(line) {
+            Ok((quantity, name, set_code, collector_number, section_override)) => {
+                deck.lines.push(DeckLine {
+                    line_number,
+                    quantity,
+                    name,
+                    set_code,
+                    collector_number,
+                    section: section_override.unwrap_or(section),
+                })
+            }
+            Err(message) => deck.errors.push(DeckParseError {
+                line_number,
+                line: line.to_string(),
+                message,
+            }),
+        }
+    }
+
+    deck
+}
+
+type TextEntry = (
+    u32,
+    String,
+    Option<String>,
+    Option<String>,
+    Option<DeckSection>,
+);
+
+/// Reads `[SB:] [4[x]] Name [(SET) [number]] [*F*] [^tags^] [[Category]]`
+fn parse_text_entry(line: &str) -> Result<TextEntry, String> {
+    let mut rest = line;
+    let mut section = None;
+
+    if rest
+        .get(..3)
+        .is_some_and(|prefix| prefix.eq_ignore_ascii_case("sb:"))
+    {
+        section = Some(DeckSection::Sideboard);
+        rest = rest[3..].trim_start();
+    }
+
+    let mut quantity = 1;
+    if let Some((first, remainder)) = rest.split_once(char::is_whitespace) {
+        let digits = first.trim_end_matches(['x', 'X']);
+        if !digits.is_empty() && digits.chars().all(|c| c.is_ascii_digit()) {
+            quantity = digits
+                .parse()
+                .map_err(|_| format!("Invalid quantity '{}'", first))?;
+            rest = remainder.trim();
+        }
+    }
+    if quantity == 0 {
+        return Err("Quantity must be at least 1".to_string());
+    }
+
+    // Archidekt categories, e.g. "[Commander{top}]" or "[Sideboard,Removal]"
+    if let Some(start) = rest.find('[') {
+        let categories = rest[start + 1..].split(']').next().unwrap_or_default();
+        section = section.or_else(|| {
+            categories
+                .split(',')
+                .map(|category| category.split('{').next().unwrap_or_default())
+                .find_map(DeckSection::from_header)
+        });
+        rest = rest[..start].trim();
+    }
+    // Archidekt tags, e.g. "^Have,#37d67a^"
+    if let Some(start) = rest.find('^') {
+        rest = rest[..start].trim();
+    }
+    // Foil and etched markers, e.g. "*F*"
+    while let Some((head, marker)) = rest.rsplit_once(' ') {
+        if marker.len() > 2 && marker.starts_with('*') && marker.ends_with('*') {
+            rest = head.trim_end();
+        } else {
+            break;
+        }
+    }
+
+    let mut set_code = None;
+    let mut collector_number = None;
+    if let Some(open) = rest.rfind(" (") {
+        if let Some(close) = rest[open..].find(')').map(|close| open + close) {
+            let code = &rest[open + 2..close];
+            if (2..=6).contains(&code.len()) && code.chars().all(|c| c.is_ascii_alphanumeric()) {
+                set_code = Some(code.to_uppercase());
+                collector_number = rest[close + 1..]
+                    .split_whitespace()
+                    .next()
+                    .map(str::to_string);
+                rest = rest[..open].trim();
+            }
+        }
+    }
+
+    let name = normalize_name(rest);
+    if name.is_empty() {
+        return Err("Missing card name".to_string());
+    }
+    Ok((quantity, name, set_code, collector_number, section))
+}
+
+/// MTGO `.dek` XML: one `<Cards Quantity="4" Sideboard="false" Name="..."/>`
+/// element per card
+fn parse_dek(content: &str) -> ParsedDeck {
+    let mut deck = ParsedDeck::default();
+    let mut offset = 0;
+
+    while let Some(start) = content[offset..].find("<Cards").map(|start| offset + start) {
+        let end = content[start..]
+            .find('>')
+            .map(|end| start + end + 1)
+            .unwrap_or(content.len());
+        let element = &content[start..end];
+        let line_number = content[..start].matches('\n').count() + 1;
+        offset = end;
+
+        let name = xml_attribute(element, "Name").map(|name| normalize_name(&name));
+        let quantity = xml_attribute(element, "Quantity")
+            .map(|quantity| quantity.trim().parse::<u32>())
+            .unwrap_or(Ok(1));
+        match (name, quantity) {
+            (Some(name), Ok(quantity)) if !name.is_empty() && quantity > 0 => {
+                let sideboard = xml_attribute(element, "Sideboard")
+                    .map(|value| value.eq_ignore_ascii_case("true"))
+                    .unwrap_or(false);
+                deck.lines.push(DeckLine {
+                    line_number,
+                    quantity,
+                    name,
+                    set_code: None,
+                    collector_number: None,
+                    section: if sideboard {
+                        DeckSection::Sideboard
+                    } else {
+                        DeckSection::Main
+                    },
+                });
+            }
+            (name, quantity) => deck.errors.push(DeckParseError {
+                line_number,
+                line: element.to_string(),
+                message: if name.is_none_or(|name| name.is_empty()) {
+                    "Missing Name attribute".to_string()
+                } else {
+                    format!("Invalid Quantity attribute: {:?}", quantity)
+                },
+            }),
+        }
+    }
+
+    if deck.lines.is_empty() && deck.errors.is_empty() {
+        deck.errors.push(DeckParseError {
+            line_number: 1,
+            line: String::new(),
+            message: "No <Cards> elements found".to_string(),
+        });
+    }
+    deck
+}
+
+/// Value of an attribute in an XML start tag, with entities decoded
+fn xml_attribute(element: &str, attribute: &str) -> Option<String> {
+    let mut search = element;
+    while let Some(position) = search.find(attribute) {
+        let preceded_by_space = search[..position]
+            .chars()
+            .last()
+            .is_some_and(char::is_whitespace);
+        let after = search[position + attribute.len()..].trim_start();
+        if let (true, Some(value)) = (preceded_by_space, after.strip_prefix('=')) {
+            let value = value.trim_start();
+            let quote = value.chars().next().filter(|&c| c == '"' || c == '\'')?;
+            let value = &value[1..];
+            let end = value.find(quote)?;
+            return Some(decode_xml_entities(&value[..end]));
+        }
+        search = &search[position + attribute.len()..];
+    }
+    None
+}
+
+fn decode_xml_entities(text: &str) -> String {
+    text.replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&amp;", "&")
+}
+
+/// CSV exports (Moxfield, Archidekt, Deckbox, ...): a header row naming at
+/// least the card name column, optionally quantity, set, collector number and
+/// section columns
+fn parse_csv(content: &str) -> ParsedDeck {
+    let mut deck = ParsedDeck::default();
+    let mut records = split_csv(content.trim_start_matches('\u{feff}')).into_iter();
+    let Some((_, header)) = records.next() else {
+        return deck;
+    };
+
+    let header: Vec<String> = header
+        .iter()
+        .map(|column| column.trim().to_lowercase())
+        .collect();
+    let column = |names: &[&str]| {
+        names
+            .iter()
+            .find_map(|name| header.iter().position(|column| column == name))
+    };
+    let Some(name_column) = column(CSV_NAME_COLUMNS) else {
+        deck.errors.push(DeckParseError {
+            line_number: 1,
+            line: header.join(","),
+            message: "CSV header has no name column".to_string(),
+        });
+        return deck;
+    };
+    let quantity_column = column(CSV_QUANTITY_COLUMNS);
+    let set_column = column(CSV_SET_COLUMNS);
+    let number_column = column(CSV_NUMBER_COLUMNS);
+    let section_column = column(CSV_SECTION_COLUMNS);
+
+    for (line_number, fields) in records {
+        let field = |index: Option<usize>| {
+            index
+                .and_then(|index| fields.get(index))
+                .map(|field| field.trim())
+                .filter(|field| !field.is_empty())
+        };
+        let Some(name) = field(Some(name_column)) else {
+            continue;
+        };
+
+        let quantity = match field(quantity_column) {
+            Some(quantity) => match quantity.trim_end_matches(['x', 'X']).parse::<u32>() {
+                Ok(quantity) if quantity > 0 => quantity,
+                _ => {
+                    deck.errors.push(DeckParseError {
+                        line_number,
+                        line: fields.join(","),
+                        message: format!("Invalid quantity '{}'", quantity),
+                    });
+                    continue;
+                }
+            },
+            None => 1,
+        };
+        let section = field(section_column)
+            .and_then(|sections| sections.split(',').find_map(DeckSection::from_header))
+            .unwrap_or_default();
+
+        deck.lines.push(DeckLine {
+            line_number,
+            quantity,
+            name: normalize_name(name),
+            set_code: field(set_column).map(str::to_uppercase),
+            collector_number: field(number_column).map(str::to_string),
+            section,
+        });
+    }
+
+    deck
+}
+
+/// Splits CSV content into records of fields, each with the line it starts
+/// on. Handles quoted fields containing commas, quotes (`""`) and newlines.
+fn split_csv(content: &str) -> Vec<(usize, Vec<String>)> {
+    let mut records = Vec::new();
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut line_number = 1;
+    let mut record_line = 1;
+    let mut chars = content.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => in_quotes = !in_quotes,
+            ',' if !in_quotes => fields.push(std::mem::take(&mut field)),
+            '\n' | '\r' if !in_quotes => {
+                if c == '\r' && chars.peek() == Some(&'\n') {
+                    chars.next();
+                }
+                fields.push(std::mem::take(&mut field));
+                if fields.iter().any(|field| !field.trim().is_empty()) {
+                    records.push((record_line, std::mem::take(&mut fields)));
+                }
+                fields.clear();
+                line_number += 1;
+                record_line = line_number;
+            }
+            c => {
+                if c == '\n' {
+                    line_number += 1;
+                }
+                field.push(c);
+            }
+        }
+    }
+    fields.push(field);
+    if fields.iter().any(|field| !field.trim().is_empty()) {
+        records.push((record_line, fields));
+    }
+
+    records
+}
+
+/// Uses the ` // ` separator for split and double-faced card names, which
+/// exports also write as ` / ` or ` /// `
+fn normalize_name(name: &str) -> String {
+    let name = name.trim();
+    if name.contains(" /// ") {
+        name.replace(" /// ", " // ")
+    } else if name.contains(" / ") && !name.contains(" // ") {
+        name.replace(" / ", " // ")
+    } else {
+        name.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Quantity, name and section of each parsed line
+    fn entries(deck: &ParsedDeck) -> Vec<(u32, &str, DeckSection)> {
+        deck.lines
+            .iter()
+            .map(|line| (line.quantity, line.name.as_str(), line.section))
+            .collect()
+    }
+
+    #[test]
+    fn arena_export() {
+        let deck = parse(
+            "About
+Name Lurrus Burn
+
+Companion
+1 Lurrus of the Dream-Den (IKO) 226
+
+Deck
+4 Lightning Bolt (2X2) 117
+4 Fire // Ice (MH2) 290
+20 Mountain (ZNR) 279
+
+Sideboard
+2 Kor Firewalker (PLST) WWK-11
+",
+            DecklistFormat::Auto,
+        );
+        assert!(deck.errors.is_empty());
+        assert_eq!(deck.name.as_deref(), Some("Lurrus Burn"));
+        assert_eq!(
+            entries(&deck),
+            vec![
+                (1, "Lurrus of the Dream-Den", DeckSection::Companion),
+                (4, "Lightning Bolt", DeckSection::Main),
+                (4, "Fire // Ice", DeckSection::Main),
+                (20, "Mountain", DeckSection::Main),
+                (2, "Kor Firewalker", DeckSection::Sideboard),
+            ]
+        );
+        let bolt = &deck.lines[1];
+        assert_eq!(bolt.line_number, 8);
+        assert_eq!(bolt.set_code.as_deref(), Some("2X2"));
+        assert_eq!(bolt.collector_number.as_deref(), Some("117"));
+        assert_eq!(deck.lines[4].collector_number.as_deref(), Some("WWK-11"));
+    }
+
+    #[test]
+    fn moxfield_text() {
+        let deck = parse(
+            "Commander (1)
+1 Krenko, Mob Boss (DDT) 52 *F*
+
+Deck (3)
+1x Sol Ring (CMR) 472
+1 Fire / Ice (DMR) 208
+1 Jace, the Mind Sculptor *E*
+
+Maybeboard (1)
+1 Goblin Recruiter
+",
+            DecklistFormat::Auto,
+        );
+        assert!(deck.errors.is_empty());
+        assert_eq!(
+            entries(&deck),
+            vec![
+                (1, "Krenko, Mob Boss", DeckSection::Commander),
+                (1, "Sol Ring", DeckSection::Main),
+                (1, "Fire // Ice", DeckSection::Main),
+                (1, "Jace, the Mind Sculptor", DeckSection::Main),
+                (1, "Goblin Recruiter", DeckSection::Maybeboard),
+            ]
+        );
+        assert_eq!(deck.lines[0].set_code.as_deref(), Some("DDT"));
+        assert_eq!(deck.lines[0].collector_number.as_deref(), Some("52"));
+    }
+
+    #[test]
+    fn archidekt_text() {
+        let deck = parse(
+            "1x Krenko, Mob Boss (ddt) 52 [Commander{top}]
+1x Sol Ring (cmr) 472 [Ramp] ^Have,#37d67a^
+2x Lightning Bolt (2x2) 117 *F* [Removal,Sideboard{noDeck}]
+1x Goblin Recruiter (vis) 80 [Maybeboard{noDeck}{noPrice}]
+",
+            DecklistFormat::Text,
+        );
+        assert!(deck.errors.is_empty());
+        assert_eq!(
+            entries(&deck),
+            vec![
+                (1, "Krenko, Mob Boss", DeckSection::Commander),
+                (1, "Sol Ring", DeckSection::Main),
+                (2, "Lightning Bolt", DeckSection::Sideboard),
+                (1, "Goblin Recruiter", DeckSection::Maybeboard),
+            ]
+        );
+        assert_eq!(deck.lines[1].set_code.as_deref(), Some("CMR"));
+        assert_eq!(deck.lines[1].collector_number.as_deref(), Some("472"));
+        assert_eq!(deck.lines[2].set_code.as_deref(), Some("2X2"));
+    }
+
+    #[test]
+    fn blank_line_starts_sideboard_without_headers() {
+        let deck = parse(
+            "4 Lightning Bolt
+56 Mountain
+
+3 Pyroblast
+",
+            DecklistFormat::Text,
+        );
+        assert_eq!(
+            entries(&deck),
+            vec![
+                (4, "Lightning Bolt", DeckSection::Main),
+                (56, "Mountain", DeckSection::Main),
+                (3, "Pyroblast", DeckSection::Sideboard),
+            ]
+        );
+    }
+
+    #[test]
+    fn blank_lines_inside_a_headed_section_are_ignored() {
+        let deck = parse(
+            "Deck
+4 Lightning Bolt
+
+56 Mountain
+",
+            DecklistFormat::Text,
+        );
+        assert!(deck
+            .lines
+            .iter()
+            .all(|line| line.section == DeckSection::Main));
+    }
+
+    #[test]
+    fn mtgo_text_sideboard_prefix() {
+        let deck = parse(
+            "4 Lightning Bolt
+SB: 2 Pyroblast
+sb: 1 Red Elemental Blast
+",
+            DecklistFormat::Text,
+        );
+        assert_eq!(
+            entries(&deck),
+            vec![
+                (4, "Lightning Bolt", DeckSection::Main),
+                (2, "Pyroblast", DeckSection::Sideboard),
+                (1, "Red Elemental Blast", DeckSection::Sideboard),
+            ]
+        );
+    }
+
+    #[test]
+    fn invalid_text_lines_are_reported() {
+        let deck = parse(
+            "4 Lightning Bolt
+0 Mountain
+// a comment
+# another
+",
+            DecklistFormat::Text,
+        );
+        assert_eq!(
+            entries(&deck),
+            vec![(4, "Lightning Bolt", DeckSection::Main)]
+        );
+        assert_eq!(deck.errors.len(), 1);
+        assert_eq!(deck.errors[0].line_number, 2);
+        assert_eq!(deck.errors[0].line, "0 Mountain");
+    }
+
+    #[test]
+    fn mtgo_dek() {
+        let deck = parse(
+            r#"<?xml version="1.0" encoding="utf-8"?>
+<Deck xmlns:xsd="http://www.w3.org/2001/XMLSchema" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance">
+  <NetDeckID>0</NetDeckID>
+  <PreconstructedDeckID>0</PreconstructedDeckID>
+  <Cards CatID="33442" Quantity="4" Sideboard="false" Name="Lightning Bolt" Annotation="0" />
+  <Cards CatID="84710" Quantity="1" Sideboard="false" Name="Elspeth, Sun&apos;s Champion" Annotation="0" />
+  <Cards CatID="86104" Quantity='2' Sideboard="true" Name="Fire / Ice" />
+  <Cards CatID="1" Quantity="x" Sideboard="false" Name="Mountain" />
+  <Cards CatID="2" Quantity="1" Sideboard="false" />
+</Deck>
+"#,
+            DecklistFormat::Auto,
+        );
+        assert_eq!(
+            entries(&deck),
+            vec![
+                (4, "Lightning Bolt", DeckSection::Main),
+                (1, "Elspeth, Sun's Champion", DeckSection::Main),
+                (2, "Fire // Ice", DeckSection::Sideboard),
+            ]
+        );
+        assert_eq!(deck.lines[0].line_number, 5);
+        let messages: Vec<&str> = deck
+            .errors
+            .iter()
+            .map(|error| error.message.as_str())
+            .collect();
+        assert_eq!(messages.len(), 2);
+        assert!(messages[0].starts_with("Invalid Quantity attribute"));
+        assert_eq!(messages[1], "Missing Name attribute");
+    }
+
+    #[test]
+    fn dek_without_cards_is_an_error() {
+        let deck = parse("<Deck></Deck>", DecklistFormat::Dek);
+        assert!(deck.lines.is_empty());
+        assert_eq!(deck.errors[0].message, "No <Cards> elements found");
+    }
+
+    #[test]
+    fn moxfield_csv() {
+        let deck = parse(
+            r#""Count","Tradelist Count","Name","Edition","Condition","Language","Foil","Tags","Last Modified","Collector Number","Alter","Proxy","Purchase Price"
+"4","4","Lightning Bolt","2x2","Near Mint","English","","","2024-03-01 12:00:00.000000","117","False","False",""
+"1","1","Jace, the Mind Sculptor","a25","Near Mint","English","foil","","2024-03-01 12:00:00.000000","62","False","False",""
+"#,
+            DecklistFormat::Auto,
+        );
+        assert!(deck.errors.is_empty());
+        assert_eq!(
+            entries(&deck),
+            vec![
+                (4, "Lightning Bolt", DeckSection::Main),
+                (1, "Jace, the Mind Sculptor", DeckSection::Main),
+            ]
+        );
+        assert_eq!(deck.lines[1].line_number, 3);
+        assert_eq!(deck.lines[1].set_code.as_deref(), Some("A25"));
+        assert_eq!(deck.lines[1].collector_number.as_deref(), Some("62"));
+    }
+
+    #[test]
+    fn archidekt_csv() {
+        let deck = parse(
+            "\u{feff}Quantity,Name,Finish,Condition,Date Added,Language,Purchase Price,Tags,Edition Name,Edition Code,Multiverse Id,Scryfall ID,Categories,Label,Collector Number
+1,\"Krenko, Mob Boss\",Normal,NM,2024-03-01,EN,0.5,,Duel Decks: Speed vs. Cunning,ddt,,,Commander,,52
+1x,Sol Ring,Foil,NM,2024-03-01,EN,1.2,\"Have,
+Staple\",Commander Legends,cmr,,,\"Ramp,Sideboard\",,472
+1,\"Kongming, \"\"Sleeping Dragon\"\"\",Normal,NM,2024-03-01,EN,,,Portal Three Kingdoms,ptk,,,Maybeboard,,9
+abc,Mountain,Normal,NM,2024-03-01,EN,,,Zendikar,zen,,,,,229
+",
+            DecklistFormat::Auto,
+        );
+        assert_eq!(
+            entries(&deck),
+            vec![
+                (1, "Krenko, Mob Boss", DeckSection::Commander),
+                (1, "Sol Ring", DeckSection::Sideboard),
+                (1, "Kongming, \"Sleeping Dragon\"", DeckSection::Maybeboard),
+            ]
+        );
+        assert_eq!(deck.lines[0].set_code.as_deref(), Some("DDT"));
+        assert_eq!(deck.lines[1].collector_number.as_deref(), Some("472"));
+        // The quoted tags span two lines, so the next record starts on line 5
+        assert_eq!(deck.lines[2].line_number, 5);
+        assert_eq!(deck.errors.len(), 1);
+        assert_eq!(deck.errors[0].line_number, 6);
+        assert_eq!(deck.errors[0].message, "Invalid quantity 'abc'");
+    }
+
+    #[test]
+    fn csv_without_name_column_is_an_error() {
+        let deck = parse("Quantity,Title\n1,Sol Ring\n", DecklistFormat::Csv);
+        assert!(deck.lines.is_empty());
+        assert_eq!(deck.errors[0].message, "CSV header has no name column");
+    }
+
+    #[test]
+    fn detects_formats() {
+        assert_eq!(
+            detect_format("<?xml version=\"1.0\"?>\n<Deck />"),
+            DecklistFormat::Dek
+        );
+        assert_eq!(detect_format("  <Deck>\n</Deck>"), DecklistFormat::Dek);
+        assert_eq!(
+            detect_format("\u{feff}\"Count\",\"Name\"\n\"1\",\"Sol Ring\""),
+            DecklistFormat::Csv
+        );
+        assert_eq!(
+            detect_format("Quantity,Card Name\n1,Sol Ring"),
+            DecklistFormat::Csv
+        );
+        // Commas in card names do not make a text list CSV
+        assert_eq!(
+            detect_format("1 Jace, the Mind Sculptor\n4 Ponder"),
+            DecklistFormat::Text
+        );
+        assert_eq!(detect_format("Name\nSol Ring"), DecklistFormat::Text);
+        assert_eq!(
+            detect_format("Deck\n4 Lightning Bolt"),
+            DecklistFormat::Text
+        );
+    }
+
+    #[test]
+    fn normalizes_split_card_names() {
+        assert_eq!(normalize_name("Fire / Ice"), "Fire // Ice");
+        assert_eq!(normalize_name("Fire /// Ice"), "Fire // Ice");
+        assert_eq!(normalize_name(" Fire // Ice "), "Fire // Ice");
+    }
+}
@@ -0,0 +1,82 @@
+use super::{
+    model::{Deck, DeckEntry, DecklistFormat, ParsedDeck},
+    parser,
+    validation::{self, DeckValidation, FormatRules},
+};
+use crate::{
+    cards::{CardModel, CardRepository},
+    error::Error,
+};
+use std::collections::HashMap;
+
+#[derive(Clone)]
+pub struct DeckService {
+    repository: CardRepository,
+}
+
+impl DeckService {
+    pub fn new(repository: CardRepository) -> Self {
+        Self { repository }
+    }
+
+    /// Parses a decklist and resolves its lines against the card database
+    pub async fn parse_deck(&self, decklist: &str, format: DecklistFormat) -> Result<Deck, Error> {
+        self.resolve(parser::parse(decklist, format)).await
+    }
+
+    /// Parses a decklist and checks it against a format's deck construction
+    /// rules, where `format` is a key of the cards' `legalities`
+    pub async fn validate_deck(
+        &self,
+        decklist: &str,
+        decklist_format: DecklistFormat,
+        format: &str,
+    ) -> Result<DeckValidation, Error> {
+        let rules = FormatRules::for_format(format).ok_or_else(|| {
+            Error::BadRequest(format!(
+                "Unknown format: {}. Expected one of: {}",
+                format,
+                FormatRules::format_names().collect::<Vec<_>>().join(", ")
+            ))
+        })?;
+        let deck = self.parse_deck(decklist, decklist_format).await?;
+        Ok(validation::validate(&deck, rules))
+    }
+
+    /// Matches each line of a parsed deck to a card by name, ignoring case.
+    /// Split and double-faced cards also match by their front face, as Arena
+    /// exports them. Set codes are kept but do not affect the match, since
+    /// cards are stored per oracle card rather than per printing.
+    pub async fn resolve(&self, parsed: ParsedDeck) -> Result<Deck, Error> {
+        let names: Vec<String> = parsed.lines.iter().map(|line| line.name.clone()).collect();
+        let mut by_name: HashMap<String, CardModel> = HashMap::new();
+        let mut by_front_face: HashMap<String, CardModel> = HashMap::new();
+        if !names.is_empty() {
+            for card in self.repository.get_by_names_or_front_faces(&names).await? {
+                if let Some((front, _)) = card.name.split_once(" // ") {
+                    by_front_face
+                        .entry(front.to_lowercase())
+                        .or_insert_with(|| card.clone());
+                }
+                by_name.entry(card.name.to_lowercase()).or_insert(card);
+            }
+        }
+
+        let mut deck = Deck {
+            name: parsed.name,
+            errors: parsed.errors,
+            ..Default::default()
+        };
+        for line in parsed.lines {
+            let key = line.name.to_lowercase();
+            match by_name.get(&key).or_else(|| by_front_face.get(&key)) {
+                Some(card) => deck.entries.push(DeckEntry {
+                    line,
+                    card: card.clone(),
+                }),
+                None => deck.unresolved.push(line),
+            }
+        }
+        Ok(deck)
+    }
+}
@@ -0,0 +1,744 @@
+use super::commander::{self, CommanderKind};
+use super::model::{Deck, DeckEntry, DeckSection};
+use crate::cards::CardModel;
+use serde::Serialize;
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+
+/// Deck construction rules of a format
+#[derive(Debug, Clone, Copy)]
+pub struct FormatRules {
+    /// Key of the format in a card's `legalities`
+    pub name: &'static str,
+    /// Minimum main deck size; for commander formats the commanders count too
+    pub min_deck_size: u32,
+    pub max_deck_size: Option<u32>,
+    pub max_sideboard_size: u32,
+    /// Copies allowed of a card that is neither restricted nor exempt
+    pub max_copies: u32,
+    /// Which cards may lead the deck, in formats with commanders
+    pub commander: Option<CommanderKind>,
+}
+
+impl FormatRules {
+    const fn constructed(name: &'static str) -> Self {
+        Self {
+            name,
+            min_deck_size: 60,
+            max_deck_size: None,
+            max_sideboard_size: 15,
+            max_copies: 4,
+            commander: None,
+        }
+    }
+
+    const fn singleton(
+        name: &'static str,
+        deck_size: u32,
+        commander: Option<CommanderKind>,
+    ) -> Self {
+        Self {
+            name,
+            min_deck_size: deck_size,
+            max_deck_size: Some(deck_size),
+            max_sideboard_size: 0,
+            max_copies: 1,
+            commander,
+        }
+    }
+
+    /// Rules of the format with the given `legalities` key
+    pub fn for_format(format: &str) -> Option<&'static FormatRules> {
+        let format = format.to_lowercase();
+        FORMATS.iter().find(|rules| rules.name == format)
+    }
+
+    pub fn format_names() -> impl Iterator<Item = &'static str> {
+        FORMATS.iter().map(|rules| rules.name)
+    }
+}
+
+const FORMATS: &[FormatRules] = &[
+    FormatRules::constructed("standard"),
+    FormatRules::constructed("future"),
+    FormatRules::constructed("historic"),
+    FormatRules::constructed("timeless"),
+    FormatRules::constructed("alchemy"),
+    FormatRules::constructed("explorer"),
+    FormatRules::constructed("pioneer"),
+    FormatRules::constructed("modern"),
+    FormatRules::constructed("legacy"),
+    FormatRules::constructed("vintage"),
+    FormatRules::constructed("pauper"),
+    FormatRules::constructed("penny"),
+    FormatRules::constructed("premodern"),
+    FormatRules::constructed("oldschool"),
+    FormatRules::singleton("commander", 100, Some(CommanderKind::Legendary)),
+    FormatRules::singleton("duel", 100, Some(CommanderKind::Legendary)),
+    FormatRules::singleton("paupercommander", 100, Some(CommanderKind::Creature)),
+    FormatRules::singleton("predh", 100, Some(CommanderKind::Legendary)),
+    FormatRules::singleton("brawl", 100, Some(CommanderKind::LegendaryOrPlaneswalker)),
+    FormatRules::singleton(
+        "standardbrawl",
+        60,
+        Some(CommanderKind::LegendaryOrPlaneswalker),
+    ),
+    FormatRules::singleton("oathbreaker", 60, Some(CommanderKind::Oathbreaker)),
+    FormatRules::singleton("gladiator", 100, None),
+];
+
+/// What a deck violates
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, schemars::JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ViolationKind {
+    Banned,
+    /// Never legal in the format, e.g. not yet released or from another game,
+    /// or with no legality recorded for the format
+    NotLegal,
+    /// More than one copy of a restricted card
+    Restricted,
+    TooManyCopies,
+    DeckTooSmall,
+    DeckTooLarge,
+    SideboardTooLarge,
+    /// A commander in a format without commanders
+    UnexpectedCommander,
+    /// A line naming no known card
+    UnknownCard,
+    /// No commander in a commander format
+    MissingCommander,
+    TooManyCommanders,
+    /// A card that cannot be a commander
+    InvalidCommander,
+    /// Two commanders without partner, friends forever, a background or
+    /// doctor's companion letting them pair
+    InvalidCommanderPair,
+    /// A card with a color outside the commanders' combined color identity
+    OutsideColorIdentity,
+}
+
+#[derive(Debug, Clone, Serialize, schemars::JsonSchema)]
+pub struct Violation {
+    pub kind: ViolationKind,
+    pub message: String,
+}
+
+/// The violations of one card
+#[derive(Debug, Clone, Serialize, schemars::JsonSchema)]
+pub struct CardViolations {
+    pub name: String,
+    /// Copies across the main deck, sideboard, commanders and companion
+    pub quantity: u32,
+    pub violations: Vec<Violation>,
+}
+
+/// Result of checking a deck against a format
+#[derive(Debug, Clone, Serialize, schemars::JsonSchema)]
+pub struct DeckValidation {
+    pub format: String,
+    /// Whether the deck has no violations
+    pub legal: bool,
+    /// Cards in the main deck, including commanders in commander formats
+    pub deck_size: u32,
+    /// Cards in the sideboard; outside commander formats this includes a
+    /// companion listed only on its own
+    pub sideboard_size: u32,
+    /// Violations of the deck as a whole, such as its size
+    pub deck_violations: Vec<Violation>,
+    /// Violations of individual cards, in decklist order
+    pub card_violations: Vec<CardViolations>,
+    /// Commanders of the deck, in commander formats
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub commanders: Vec<String>,
+    /// Combined color identity of the commanders, empty if colorless
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub color_identity: Option<Vec<String>>,
+}
+
+impl DeckValidation {
+    /// Records a violation of the named card
+    pub fn add_card_violation(&mut self, name: &str, quantity: u32, violation: Violation) {
+        match self
+            .card_violations
+            .iter_mut()
+            .find(|card| card.name == name)
+        {
+            Some(card) => card.violations.push(violation),
+            None => self.card_violations.push(CardViolations {
+                name: name.to_string(),
+                quantity,
+                violations: vec![violation],
+            }),
+        }
+        self.legal = false;
+    }
+
+    pub fn add_deck_violation(&mut self, violation: Violation) {
+        self.deck_violations.push(violation);
+        self.legal = false;
+    }
+}
+
+/// How many copies of a card a deck may hold, from its type line and oracle
+/// text: basic lands and cards saying "A deck can have any number of cards
+/// named ..." are unlimited, and cards like Seven Dwarves set their own limit
+pub fn copy_limit_exception(card: &CardModel) -> Option<CopyLimit> {
+    if card
+        .type_line
+        .as_deref()
+        .is_some_and(|type_line| type_line.contains("Basic") && type_line.contains("Land"))
+    {
+        return Some(CopyLimit::Unlimited);
+    }
+
+    let oracle_text = card.oracle_text.as_deref()?.to_lowercase();
+    if oracle_text.contains("a deck can have any number of cards named") {
+        return Some(CopyLimit::Unlimited);
+    }
+    let (_, rest) = oracle_text.split_once("a deck can have up to ")?;
+    let (count, rest) = rest.split_once(' ')?;
+    if !rest.starts_with("cards named") {
+        return None;
+    }
+    let count = match count {
+        "one" => 1,
+        "two" => 2,
+        "three" => 3,
+        "four" => 4,
+        "five" => 5,
+        "six" => 6,
+        "seven" => 7,
+        "eight" => 8,
+        "nine" => 9,
+        "ten" => 10,
+        number => number.parse().ok()?,
+    };
+    Some(CopyLimit::AtMost(count))
+}
+
+/// Legality of a card in a format, as recorded in its `legalities`
+pub(super) fn legality<'a>(card: &'a CardModel, format: &str) -> Option<&'a str> {
+    card.legalities
+        .as_ref()
+        .and_then(|legalities| legalities.get(format))
+        .and_then(|legality| legality.as_str())
+}
+
+/// Copy limit set by a card itself
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CopyLimit {
+    Unlimited,
+    AtMost(u32),
+}
+
+/// Entries that are part of the deck in the format: everything but the
+/// maybeboard, and a companion only if it is not also in the sideboard
+fn deck_entries(deck: &Deck) -> Vec<&DeckEntry> {
+    deck.entries
+        .iter()
+        .filter(|entry| match entry.line.section {
+            DeckSection::Maybeboard => false,
+            DeckSection::Companion => !deck
+                .section(DeckSection::Sideboard)
+                .any(|sideboard| sideboard.card.id == entry.card.id),
+            _ => true,
+        })
+        .collect()
+}
+
+/// Checks a resolved deck against a format's rules: card legality (banned,
+/// restricted, not legal), copy limits, deck size and sideboard size, and in
+/// commander formats the commanders and color identity
+pub fn validate(deck: &Deck, rules: &FormatRules) -> DeckValidation {
+    let deck = match rules.commander {
+        Some(_) => commander::with_sideboard_commanders(deck),
+        None => Cow::Borrowed(deck),
+    };
+    let entries = deck_entries(&deck);
+    let count = |sections: &[DeckSection]| -> u32 {
+        entries
+            .iter()
+            .filter(|entry| sections.contains(&entry.line.section))
+            .map(|entry| entry.line.quantity)
+            .sum()
+    };
+    // A companion sits in the sideboard, except in commander formats where
+    // it stays outside the game on its own
+    let (deck_size, sideboard_size) = if rules.commander.is_some() {
+        (
+            count(&[DeckSection::Main, DeckSection::Commander]),
+            count(&[DeckSection::Sideboard]),
+        )
+    } else {
+        (
+            count(&[DeckSection::Main]),
+            count(&[DeckSection::Sideboard, DeckSection::Companion]),
+        )
+    };
+
+    let mut validation = DeckValidation {
+        format: rules.name.to_string(),
+        legal: true,
+        deck_size,
+        sideboard_size,
+        deck_violations: Vec::new(),
+        card_violations: Vec::new(),
+        commanders: Vec::new(),
+        color_identity: None,
+    };
+
+    for line in &deck.unresolved {
+        validation.add_card_violation(
+            &line.name,
+            line.quantity,
+            Violation {
+                kind: ViolationKind::UnknownCard,
+                message: format!("Line {}: no card named {}", line.line_number, line.name),
+            },
+        );
+    }
+
+    // Copies per card across every part of the deck, in decklist order
+    let mut copies: BTreeMap<i32, (usize, &CardModel, u32)> = BTreeMap::new();
+    for (position, entry) in entries.iter().enumerate() {
+        copies
+            .entry(entry.card.id)
+            .or_insert((position, &entry.card, 0))
+            .2 += entry.line.quantity;
+    }
+    let mut copies: Vec<(usize, &CardModel, u32)> = copies.into_values().collect();
+    copies.sort_by_key(|(position, _, _)| *position);
+
+    for (_, card, quantity) in copies {
+        match legality(card, rules.name) {
+            Some("banned") => validation.add_card_violation(
+                &card.name,
+                quantity,
+                Violation {
+                    kind: ViolationKind::Banned,
+                    message: format!("{} is banned in {}", card.name, rules.name),
+                },
+            ),
+            Some("not_legal") => validation.add_card_violation(
+                &card.name,
+                quantity,
+                Violation {
+                    kind: ViolationKind::NotLegal,
+                    message: format!("{} is not legal in {}", card.name, rules.name),
+                },
+            ),
+            Some("restricted") if quantity > 1 => validation.add_card_violation(
+                &card.name,
+                quantity,
+                Violation {
+                    kind: ViolationKind::Restricted,
+                    message: format!(
+                        "{} is restricted in {}: 1 copy allowed, found {}",
+                        card.name, rules.name, quantity
+                    ),
+                },
+            ),
+            Some("legal" | "restricted") => {}
+            // Without a known legality the card cannot be shown to be legal
+            _ => validation.add_card_violation(
+                &card.name,
+                quantity,
+                Violation {
+                    kind: ViolationKind::NotLegal,
+                    message: format!(
+                        "{} has no known legality in {}, so it is treated as not legal",
+                        card.name, rules.name
+                    ),
+                },
+            ),
+        }
+
+        let limit = match copy_limit_exception(card) {
+            Some(CopyLimit::Unlimited) => None,
+            Some(CopyLimit::AtMost(limit)) => Some(limit),
+            None => Some(rules.max_copies),
+        };
+        if let Some(limit) = limit.filter(|&limit| quantity > limit) {
+            validation.add_card_violation(
+                &card.name,
+                quantity,
+                Violation {
+                    kind: ViolationKind::TooManyCopies,
+                    message: format!(
+                        "{} copies of {}, at most {} allowed",
+                        quantity, card.name, limit
+                    ),
+                },
+            );
+        }
+    }
+
+    if let Some(kind) = rules.commander {
+        commander::validate_commanders(&entries, kind, &mut validation);
+    } else {
+        let commanders = count(&[DeckSection::Commander]);
+        if commanders > 0 {
+            validation.add_deck_violation(Violation {
+                kind: ViolationKind::UnexpectedCommander,
+                message: format!(
+                    "{} has no commanders, but the deck lists {}",
+                    rules.name, commanders
+                ),
+            });
+        }
+    }
+    if deck_size < rules.min_deck_size {
+        validation.add_deck_violation(Violation {
+            kind: ViolationKind::DeckTooSmall,
+            message: format!(
+                "The deck has {} cards, at least {} required",
+                deck_size, rules.min_deck_size
+            ),
+        });
+    }
+    if let Some(max_deck_size) = rules.max_deck_size.filter(|&max| deck_size > max) {
+        validation.add_deck_violation(Violation {
+            kind: ViolationKind::DeckTooLarge,
+            message: format!(
+                "The deck has {} cards, at most {} allowed",
+                deck_size, max_deck_size
+            ),
+        });
+    }
+    if sideboard_size > rules.max_sideboard_size {
+        validation.add_deck_violation(Violation {
+            kind: ViolationKind::SideboardTooLarge,
+            message: format!(
+                "The sideboard has {} cards, at most {} allowed",
+                sideboard_size, rules.max_sideboard_size
+            ),
+        });
+    }
+
+    validation
+}
+
+#[cfg(test)]
+pub(super) mod tests {
+    use super::*;
+    use crate::decks::model::DeckLine;
+    use serde_json::{json, Map, Value};
+
+    /// A card legal in every known format
+    pub(in crate::decks) fn card(
+        id: i32,
+        name: &str,
+        type_line: &str,
+        oracle_text: &str,
+    ) -> CardModel {
+        let legalities: Map<String, Value> = FormatRules::format_names()
+            .map(|format| (format.to_string(), json!("legal")))
+            .collect();
+        let main_type = ["Creature", "Planeswalker", "Land", "Instant", "Sorcery"]
+            .into_iter()
+            .find(|main_type| type_line.contains(main_type))
+            .unwrap_or("Unknown");
+        CardModel {
+            id,
+            name: name.to_string(),
+            main_type: main_type.to_string().into(),
+            type_line: Some(type_line.to_string()),
+            oracle_text: Some(oracle_text.to_string()),
+            keywords: Some(Vec::new()),
+            cmc: None,
+            mana_cost: None,
+            colors: None,
+            color_identity: Some(Vec::new()),
+            power: None,
+            toughness: None,
+            games: None,
+            legalities: Some(Value::Object(legalities)),
+            reserved: None,
+            game_changer: None,
+            embedding: None,
+        }
+    }
+
+    pub(in crate::decks) fn entry(
+        card: &CardModel,
+        quantity: u32,
+        section: DeckSection,
+    ) -> DeckEntry {
+        DeckEntry {
+            line: DeckLine {
+                line_number: 1,
+                quantity,
+                name: card.name.clone(),
+                set_code: None,
+                collector_number: None,
+                section,
+            },
+            card: card.clone(),
+        }
+    }
+
+    pub(in crate::decks) fn deck(entries: Vec<DeckEntry>) -> Deck {
+        Deck {
+            entries,
+            ..Default::default()
+        }
+    }
+
+    pub(in crate::decks) fn with_legality(
+        mut card: CardModel,
+        format: &str,
+        legality: &str,
+    ) -> CardModel {
+        if let Some(Value::Object(legalities)) = &mut card.legalities {
+            legalities.insert(format.to_string(), json!(legality));
+        }
+        card
+    }
+
+    fn mountain() -> CardModel {
+        card(1, "Mountain", "Basic Land — Mountain", "({T}: Add {R}.)")
+    }
+
+    fn lightning_bolt() -> CardModel {
+        card(
+            2,
+            "Lightning Bolt",
+            "Instant",
+            "Lightning Bolt deals 3 damage to any target.",
+        )
+    }
+
+    /// 4 Lightning Bolt and 56 Mountain, legal in every constructed format
+    fn burn_main_deck() -> Vec<DeckEntry> {
+        vec![
+            entry(&lightning_bolt(), 4, DeckSection::Main),
+            entry(&mountain(), 56, DeckSection::Main),
+        ]
+    }
+
+    fn violation_kinds(validation: &DeckValidation, name: &str) -> Vec<ViolationKind> {
+        validation
+            .card_violations
+            .iter()
+            .filter(|card| card.name == name)
+            .flat_map(|card| card.violations.iter().map(|violation| violation.kind))
+            .collect()
+    }
+
+    fn rules(format: &str) -> &'static FormatRules {
+        FormatRules::for_format(format).unwrap()
+    }
+
+    #[test]
+    fn basic_lands_are_unlimited() {
+        let snow_forest = card(
+            3,
+            "Snow-Covered Forest",
+            "Basic Snow Land — Forest",
+            "({T}: Add {G}.)",
+        );
+        assert_eq!(
+            copy_limit_exception(&mountain()),
+            Some(CopyLimit::Unlimited)
+        );
+        assert_eq!(
+            copy_limit_exception(&snow_forest),
+            Some(CopyLimit::Unlimited)
+        );
+
+        let validation = validate(&deck(burn_main_deck()), rules("modern"));
+        assert!(validation.legal, "{:?}", validation);
+        assert_eq!(validation.deck_size, 60);
+    }
+
+    #[test]
+    fn nonbasic_lands_are_limited() {
+        let ancient_tomb = card(
+            4,
+            "Ancient Tomb",
+            "Land",
+            "{T}: Add {C}{C}. Ancient Tomb deals 2 damage to you.",
+        );
+        assert_eq!(copy_limit_exception(&ancient_tomb), None);
+    }
+
+    #[test]
+    fn any_number_of_cards_named() {
+        let rats = card(
+            5,
+            "Relentless Rats",
+            "Creature — Rat",
+            "Relentless Rats gets +1/+1 for each other creature on the battlefield named Relentless Rats.\nA deck can have any number of cards named Relentless Rats.",
+        );
+        assert_eq!(copy_limit_exception(&rats), Some(CopyLimit::Unlimited));
+
+        let validation = validate(
+            &deck(vec![
+                entry(&rats, 30, DeckSection::Main),
+                entry(&mountain(), 30, DeckSection::Main),
+            ]),
+            rules("legacy"),
+        );
+        assert!(validation.legal, "{:?}", validation);
+    }
+
+    #[test]
+    fn cards_with_their_own_limit() {
+        let dwarves = card(
+            6,
+            "Seven Dwarves",
+            "Creature — Dwarf",
+            "Seven Dwarves gets +1/+1 for each other creature named Seven Dwarves you control.\nA deck can have up to seven cards named Seven Dwarves.",
+        );
+        let nazgul = card(
+            7,
+            "Nazgûl",
+            "Creature — Wraith Knight",
+            "Deathtouch\nWhen Nazgûl enters, the Ring tempts you.\nWhenever the Ring tempts you, put a +1/+1 counter on each Wraith you control.\nA deck can have up to nine cards named Nazgûl.",
+        );
+        assert_eq!(copy_limit_exception(&dwarves), Some(CopyLimit::AtMost(7)));
+        assert_eq!(copy_limit_exception(&nazgul), Some(CopyLimit::AtMost(9)));
+
+        let validation = validate(
+            &deck(vec![
+                entry(&dwarves, 8, DeckSection::Main),
+                entry(&nazgul, 9, DeckSection::Main),
+                entry(&mountain(), 43, DeckSection::Main),
+            ]),
+            rules("legacy"),
+        );
+        assert_eq!(
+            violation_kinds(&validation, "Seven Dwarves"),
+            vec![ViolationKind::TooManyCopies]
+        );
+        assert!(violation_kinds(&validation, "Nazgûl").is_empty());
+    }
+
+    #[test]
+    fn copies_are_counted_across_sections() {
+        let mut entries = burn_main_deck();
+        entries.push(entry(&lightning_bolt(), 1, DeckSection::Sideboard));
+        let validation = validate(&deck(entries), rules("modern"));
+        assert_eq!(
+            violation_kinds(&validation, "Lightning Bolt"),
+            vec![ViolationKind::TooManyCopies]
+        );
+        assert_eq!(validation.card_violations[0].quantity, 5);
+    }
+
+    #[test]
+    fn restricted_card_with_two_copies() {
+        let time_walk = with_legality(
+            card(
+                8,
+                "Time Walk",
+                "Sorcery",
+                "Take an extra turn after this one.",
+            ),
+            "vintage",
+            "restricted",
+        );
+        let mut entries = burn_main_deck();
+        entries.push(entry(&time_walk, 2, DeckSection::Main));
+        let validation = validate(&deck(entries), rules("vintage"));
+        assert!(!validation.legal);
+        assert_eq!(
+            violation_kinds(&validation, "Time Walk"),
+            vec![ViolationKind::Restricted]
+        );
+
+        let mut entries = burn_main_deck();
+        entries.push(entry(&time_walk, 1, DeckSection::Main));
+        assert!(validate(&deck(entries), rules("vintage")).legal);
+    }
+
+    #[test]
+    fn banned_and_not_legal_cards() {
+        let mut entries = burn_main_deck();
+        entries.push(entry(
+            &with_legality(card(9, "Ponder", "Sorcery", ""), "modern", "banned"),
+            1,
+            DeckSection::Main,
+        ));
+        entries.push(entry(
+            &with_legality(card(10, "Brainstorm", "Instant", ""), "modern", "not_legal"),
+            1,
+            DeckSection::Main,
+        ));
+        let validation = validate(&deck(entries), rules("modern"));
+        assert_eq!(
+            violation_kinds(&validation, "Ponder"),
+            vec![ViolationKind::Banned]
+        );
+        assert_eq!(
+            violation_kinds(&validation, "Brainstorm"),
+            vec![ViolationKind::NotLegal]
+        );
+    }
+
+    #[test]
+    fn missing_legality_is_not_legal() {
+        let mut no_key = card(11, "Unknown Shores", "Land", "");
+        if let Some(Value::Object(legalities)) = &mut no_key.legalities {
+            legalities.remove("modern");
+        }
+        let mut no_legalities = card(12, "Mystery Card", "Instant", "");
+        no_legalities.legalities = None;
+
+        let mut entries = burn_main_deck();
+        entries.push(entry(&no_key, 1, DeckSection::Main));
+        entries.push(entry(&no_legalities, 1, DeckSection::Main));
+        let validation = validate(&deck(entries), rules("modern"));
+        assert!(!validation.legal);
+        assert_eq!(
+            violation_kinds(&validation, "Unknown Shores"),
+            vec![ViolationKind::NotLegal]
+        );
+        assert_eq!(
+            violation_kinds(&validation, "Mystery Card"),
+            vec![ViolationKind::NotLegal]
+        );
+    }
+
+    #[test]
+    fn companion_in_companion_section_and_sideboard_counts_once() {
+        let lurrus = card(
+            13,
+            "Lurrus of the Dream-Den",
+            "Legendary Creature — Cat Nightmare",
+            "Companion — Each permanent card in your starting deck has mana value 2 or less.",
+        );
+        let mut entries = burn_main_deck();
+        entries.push(entry(&lurrus, 1, DeckSection::Companion));
+        entries.push(entry(&lurrus, 1, DeckSection::Sideboard));
+        entries.push(entry(&lightning_bolt(), 14, DeckSection::Maybeboard));
+        let validation = validate(&deck(entries), rules("modern"));
+        assert!(validation.legal, "{:?}", validation);
+        assert_eq!(validation.sideboard_size, 1);
+
+        // Listed only as a companion, it still takes a sideboard slot
+        let mut entries = burn_main_deck();
+        entries.push(entry(&lurrus, 1, DeckSection::Companion));
+        let validation = validate(&deck(entries), rules("modern"));
+        assert_eq!(validation.sideboard_size, 1);
+    }
+
+    #[test]
+    fn deck_and_sideboard_sizes() {
+        let mut entries = vec![entry(&mountain(), 59, DeckSection::Main)];
+        entries.push(entry(&mountain(), 16, DeckSection::Sideboard));
+        let validation = validate(&deck(entries), rules("pauper"));
+        let kinds: Vec<ViolationKind> = validation
+            .deck_violations
+            .iter()
+            .map(|violation| violation.kind)
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                ViolationKind::DeckTooSmall,
+                ViolationKind::SideboardTooLarge
+            ]
+        );
+    }
+}
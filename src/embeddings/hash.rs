@@ -0,0 +1,124 @@
+use super::{tokenize, Embedder};
+use crate::error::Error;
+use crate::similarity::vector::normalize;
+
+/// A deterministic stand-in embedder based on feature hashing.
+///
+/// Each token and adjacent token pair is hashed into a signed bucket, so texts
+/// sharing words end up close together. No model file is needed, which makes it
+/// suitable for tests and local development, but the vectors carry no semantic
+/// meaning beyond word overlap.
+#[derive(Debug, Clone)]
+pub struct HashEmbedder {
+    dimension: usize,
+    model_id: String,
+}
+
+impl HashEmbedder {
+    pub const DEFAULT_DIMENSION: usize = 384;
+
+    pub fn new(dimension: usize) -> Self {
+        Self {
+            dimension,
+            model_id: format!("hash-{}", dimension),
+        }
+    }
+
+    /// 64-bit FNV-1a, stable across platforms and releases
+    fn hash(feature: &str) -> u64 {
+        feature.bytes().fold(0xcbf29ce484222325, |hash, byte| {
+            (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+        })
+    }
+
+    fn add_feature(&self, vector: &mut [f32], feature: &str, weight: f32) {
+        let hash = Self::hash(feature);
+        let bucket = (hash % self.dimension as u64) as usize;
+        let sign = if hash >> 63 == 0 { 1.0 } else { -1.0 };
+        vector[bucket] += sign * weight;
+    }
+}
+
+impl Embedder for HashEmbedder {
+    fn model_id(&self) -> &str {
+        &self.model_id
+    }
+
+    fn dimension(&self) -> usize {
+        self.dimension
+    }
+
+    fn embed(&self, text: &str) -> Result<Vec<f32>, Error> {
+        if self.dimension == 0 {
+            return Err(Error::BadRequest(
+                "Embedding dimension must be greater than zero".to_string(),
+            ));
+        }
+
+        let mut vector = vec![0.0; self.dimension];
+        let tokens: Vec<String> = tokenize(text).collect();
+        for token in &tokens {
+            self.add_feature(&mut vector, token, 1.0);
+        }
+        for pair in tokens.windows(2) {
+            self.add_feature(&mut vector, &format!("{} {}", pair[0], pair[1]), 0.5);
+        }
+
+        normalize(&mut vector);
+        Ok(vector)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::similarity::vector::dot;
+
+    #[test]
+    fn embeddings_are_deterministic() {
+        let embedder = HashEmbedder::new(64);
+        let text = "Destroy target creature. Its controller gains 2 life.";
+        assert_eq!(embedder.embed(text).unwrap(), embedder.embed(text).unwrap());
+        assert_eq!(
+            HashEmbedder::new(64).embed(text).unwrap(),
+            embedder.embed(text).unwrap()
+        );
+    }
+
+    #[test]
+    fn embeddings_are_normalized() {
+        let embedder = HashEmbedder::new(32);
+        for text in [
+            "Flying",
+            "Draw two cards, then discard a card.",
+            "a a a a b",
+        ] {
+            let vector = embedder.embed(text).unwrap();
+            assert_eq!(vector.len(), 32);
+            assert!((dot(&vector, &vector) - 1.0).abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn shared_words_are_closer() {
+        let embedder = HashEmbedder::new(256);
+        let query = embedder.embed("destroy target creature").unwrap();
+        let related = embedder.embed("destroy target artifact").unwrap();
+        let unrelated = embedder.embed("draw two cards").unwrap();
+        assert!(dot(&query, &related) > dot(&query, &unrelated));
+    }
+
+    #[test]
+    fn text_without_tokens_embeds_to_zero() {
+        let vector = HashEmbedder::new(8).embed("  ... ").unwrap();
+        assert!(vector.iter().all(|&value| value == 0.0));
+    }
+
+    #[test]
+    fn zero_dimension_is_rejected() {
+        assert!(matches!(
+            HashEmbedder::new(0).embed("Flying"),
+            Err(Error::BadRequest(_))
+        ));
+    }
+}
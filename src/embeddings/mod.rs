@@ -0,0 +1,60 @@
+//! Text embedding for semantic search.
+//!
+//! An [`Embedder`] turns free text into a vector in the same space as the
+//! `card.embedding` column so it can be compared with pgvector. Embedders run
+//! locally and offline.
+
+mod hash;
+mod static_model;
+
+pub use hash::HashEmbedder;
+pub use static_model::StaticEmbedder;
+
+use crate::error::Error;
+use std::sync::Arc;
+
+pub trait Embedder: Send + Sync {
+    /// Identifier of the model producing the vectors
+    fn model_id(&self) -> &str;
+
+    /// Number of dimensions of the produced vectors
+    fn dimension(&self) -> usize;
+
+    /// Embeds a piece of text into an L2-normalized vector
+    fn embed(&self, text: &str) -> Result<Vec<f32>, Error>;
+}
+
+/// Builds the embedder configured through the environment, if any:
+///
+/// - `EMBEDDING_MODEL_PATH` loads a [`StaticEmbedder`] from a word-vector file
+/// - `EMBEDDER=hash` uses the deterministic [`HashEmbedder`], with
+///   `EMBEDDING_DIMENSION` dimensions (default: 384)
+pub fn from_env() -> Result<Option<Arc<dyn Embedder>>, Error> {
+    if let Ok(path) = std::env::var("EMBEDDING_MODEL_PATH") {
+        let embedder = StaticEmbedder::load(&path)?;
+        return Ok(Some(Arc::new(embedder)));
+    }
+
+    match std::env::var("EMBEDDER").as_deref() {
+        Ok("hash") => {
+            let dimension = match std::env::var("EMBEDDING_DIMENSION") {
+                Ok(value) => value.parse().map_err(|_| {
+                    Error::BadRequest(format!("Invalid EMBEDDING_DIMENSION '{}'", value))
+                })?,
+                Err(_) => HashEmbedder::DEFAULT_DIMENSION,
+            };
+            Ok(Some(Arc::new(HashEmbedder::new(dimension))))
+        }
+        Ok(other) => Err(Error::BadRequest(format!("Unknown EMBEDDER '{}'", other))),
+        Err(_) => Ok(None),
+    }
+}
+
+/// Splits text into lowercase alphanumeric tokens, keeping apostrophes and
+/// hyphens inside words (e.g. "opponent's", "lifelink")
+pub(crate) fn tokenize(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|c: char| !(c.is_alphanumeric() || c == '\'' || c == '-'))
+        .map(|token| token.trim_matches(|c| c == '\'' || c == '-'))
+        .filter(|token| !token.is_empty())
+        .map(|token| token.to_lowercase())
+}
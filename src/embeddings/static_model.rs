@@ -0,0 +1,174 @@
+use super::{tokenize, Embedder};
+use crate::error::Error;
+use crate::similarity::vector::normalize;
+use std::collections::HashMap;
+use std::path::Path;
+
+/// An embedder backed by a static word-vector model loaded from disk.
+///
+/// The model file uses the plain text word2vec/GloVe format: one word per
+/// line followed by its vector components, separated by spaces. An optional
+/// `<vocabulary size> <dimension>` header line is skipped. Text is embedded as
+/// the mean of the vectors of its known tokens.
+#[derive(Debug, Clone)]
+pub struct StaticEmbedder {
+    model_id: String,
+    dimension: usize,
+    vectors: HashMap<String, Vec<f32>>,
+}
+
+impl StaticEmbedder {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let path = path.as_ref();
+        let contents = std::fs::read_to_string(path).map_err(|e| {
+            tracing::error!("Failed to read embedding model {:?}: {:?}", path, e);
+            Error::BadRequest(format!("Could not read embedding model {}", path.display()))
+        })?;
+        let model_id = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "static".to_string());
+
+        let embedder = Self::parse(model_id, &contents)?;
+        tracing::info!(
+            "Loaded embedding model {} ({} words, {} dimensions)",
+            embedder.model_id,
+            embedder.vectors.len(),
+            embedder.dimension
+        );
+        Ok(embedder)
+    }
+
+    pub fn parse(model_id: impl Into<String>, contents: &str) -> Result<Self, Error> {
+        let mut dimension = 0;
+        let mut vectors = HashMap::new();
+
+        for (line_number, line) in contents.lines().enumerate() {
+            let mut parts = line.split_whitespace();
+            let Some(word) = parts.next() else {
+                continue;
+            };
+            let values: Result<Vec<f32>, _> = parts.map(str::parse::<f32>).collect();
+            let values = values.map_err(|_| {
+                Error::BadRequest(format!(
+                    "Invalid vector on line {} of the embedding model",
+                    line_number + 1
+                ))
+            })?;
+
+            // word2vec header: "<vocabulary size> <dimension>"
+            if line_number == 0 && values.len() == 1 && word.parse::<usize>().is_ok() {
+                continue;
+            }
+
+            if dimension == 0 {
+                dimension = values.len();
+            } else if values.len() != dimension {
+                return Err(Error::BadRequest(format!(
+                    "Expected {} dimensions on line {} of the embedding model, found {}",
+                    dimension,
+                    line_number + 1,
+                    values.len()
+                )));
+            }
+            vectors.insert(word.to_lowercase(), values);
+        }
+
+        if dimension == 0 {
+            return Err(Error::BadRequest(
+                "Embedding model contains no vectors".to_string(),
+            ));
+        }
+
+        Ok(Self {
+            model_id: model_id.into(),
+            dimension,
+            vectors,
+        })
+    }
+}
+
+impl Embedder for StaticEmbedder {
+    fn model_id(&self) -> &str {
+        &self.model_id
+    }
+
+    fn dimension(&self) -> usize {
+        self.dimension
+    }
+
+    fn embed(&self, text: &str) -> Result<Vec<f32>, Error> {
+        let mut vector = vec![0.0; self.dimension];
+        let mut known_tokens = 0;
+        for token in tokenize(text) {
+            if let Some(word_vector) = self.vectors.get(&token) {
+                vector
+                    .iter_mut()
+                    .zip(word_vector)
+                    .for_each(|(v, w)| *v += w);
+                known_tokens += 1;
+            }
+        }
+
+        if known_tokens == 0 {
+            return Err(Error::BadRequest(format!(
+                "None of the words in '{}' are known to embedding model {}",
+                text, self.model_id
+            )));
+        }
+
+        normalize(&mut vector);
+        Ok(vector)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MODEL: &str = "flying 1 0 0\nTrample 0 1 0\nhaste 0 0 1\n";
+
+    #[test]
+    fn parses_models_with_and_without_a_header() {
+        let plain = StaticEmbedder::parse("plain", MODEL).unwrap();
+        let headed = StaticEmbedder::parse("headed", &format!("3 3\n{}", MODEL)).unwrap();
+        for embedder in [&plain, &headed] {
+            assert_eq!(embedder.dimension(), 3);
+            assert_eq!(embedder.vectors.len(), 3);
+            assert!(!embedder.vectors.contains_key("3"));
+        }
+        assert_eq!(headed.embed("trample").unwrap(), vec![0.0, 1.0, 0.0]);
+    }
+
+    #[test]
+    fn ragged_rows_are_rejected() {
+        let error = StaticEmbedder::parse("ragged", "flying 1 0 0\nhaste 0 1\n").unwrap_err();
+        assert!(
+            matches!(&error, Error::BadRequest(message) if message.contains("Expected 3 dimensions on line 2")),
+            "{:?}",
+            error
+        );
+        assert!(StaticEmbedder::parse("invalid", "flying 1 x 0\n").is_err());
+        assert!(StaticEmbedder::parse("empty", "\n\n").is_err());
+    }
+
+    #[test]
+    fn embeds_the_normalized_mean_of_known_tokens() {
+        let embedder = StaticEmbedder::parse("model", MODEL).unwrap();
+        let vector = embedder.embed("Flying, haste and ward").unwrap();
+        let expected = std::f32::consts::FRAC_1_SQRT_2;
+        assert!((vector[0] - expected).abs() < 1e-6);
+        assert_eq!(vector[1], 0.0);
+        assert!((vector[2] - expected).abs() < 1e-6);
+    }
+
+    #[test]
+    fn out_of_vocabulary_text_is_rejected_rather_than_embedded_as_zero() {
+        // A zero vector has no direction, so cosine distances to it are undefined
+        let embedder = StaticEmbedder::parse("model", MODEL).unwrap();
+        assert!(matches!(
+            embedder.embed("deathtouch lifelink"),
+            Err(Error::BadRequest(_))
+        ));
+    }
+}
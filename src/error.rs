@@ -1,4 +1,6 @@
 use derive_more::Display;
+use rmcp::model::ErrorData as McpError;
+use serde_json::json;
 
 #[derive(Debug, Display, Clone)]
 pub enum Error {
@@ -6,4 +8,19 @@ pub enum Error {
     InternalServerError,
     #[display(fmt = "NotFound: {}", _0)]
     NotFound(String),
+    #[display(fmt = "BadRequest: {}", _0)]
+    BadRequest(String),
+}
+
+/// Maps errors to MCP errors so caller mistakes are reported as invalid
+/// parameters rather than server failures
+impl From<Error> for McpError {
+    fn from(error: Error) -> Self {
+        let data = Some(json!({ "error": error.to_string() }));
+        match error {
+            Error::BadRequest(_) => McpError::invalid_params("bad_request", data),
+            Error::NotFound(_) => McpError::resource_not_found("not_found", data),
+            Error::InternalServerError => McpError::internal_error("internal_server_error", data),
+        }
+    }
 }
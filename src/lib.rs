@@ -1,10 +1,17 @@
 pub mod cards;
+pub mod decks;
+pub mod embeddings;
 pub mod error;
 pub mod mcp;
+pub mod similarity;
 
 use cards::service::CardService;
+use decks::DeckService;
 
 #[derive(Clone)]
 pub struct AppState {
     pub card_service: CardService,
+    pub deck_service: DeckService,
+    /// Whether database administration tools (vector indexes, ...) are exposed
+    pub admin_tools: bool,
 }
@@ -1,11 +1,108 @@
+use clap::{Parser, Subcommand};
 use dotenv::dotenv;
 use manaql_mcp::mcp::McpServer;
 use manaql_mcp::{
-    cards::{repository::CardRepository, service::CardService},
+    cards::{
+        backfill::{BackfillMode, BackfillOptions},
+        evaluation::GoldenSet,
+        graph::GraphFormat,
+        mcp::SearchFilters,
+        repository::CardRepository,
+        service::CardService,
+        DistanceMetric, VectorSearchOptions,
+    },
+    decks::DeckService,
+    embeddings,
+    similarity::ann::{self, VectorBackend},
     AppState,
 };
 use sqlx::postgres::PgPoolOptions;
+use std::path::PathBuf;
 use tracing;
+use tracing_subscriber::EnvFilter;
+
+#[derive(Parser)]
+#[command(
+    version,
+    about = "ManaQL MCP server for Magic: The Gathering card data"
+)]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Run the MCP server over stdio (the default)
+    Serve,
+    /// Compute card embeddings with the configured embedder
+    BackfillEmbeddings {
+        /// Which cards to embed: missing, stale (different dimension) or all
+        #[arg(long, default_value = "missing")]
+        mode: BackfillMode,
+        /// Cards embedded and written per transaction
+        #[arg(long, default_value_t = 100)]
+        batch_size: i64,
+        /// Resume after this card ID, as reported by an earlier run
+        #[arg(long)]
+        after_id: Option<i32>,
+        /// Stop after this many cards
+        #[arg(long)]
+        max_cards: Option<i64>,
+        /// Store vectors in the embedder's named embedding space instead of card.embedding
+        #[arg(long)]
+        named_space: bool,
+    },
+    /// Score find_similar_cards against a golden file of expected similar cards
+    EvaluateSimilarity {
+        /// JSON array of {"card": ..., "similar": [...]} entries
+        #[arg(long)]
+        golden: PathBuf,
+        /// Number of results scored per query
+        #[arg(long, default_value_t = 10)]
+        k: usize,
+        /// Named embedding space (model id) to evaluate instead of card.embedding,
+        /// as filled by `backfill-embeddings --named-space`
+        #[arg(long)]
+        embedding_space: Option<String>,
+        /// Distance metric: cosine, l2 or inner_product
+        #[arg(long, default_value = "cosine")]
+        metric: DistanceMetric,
+        /// hnsw.ef_search for the vector queries
+        #[arg(long)]
+        ef_search: Option<u32>,
+        /// ivfflat.probes for the vector queries
+        #[arg(long)]
+        probes: Option<u32>,
+        /// Write the full per-query report as JSON to this file
+        #[arg(long)]
+        output: Option<PathBuf>,
+    },
+    /// Export the k-nearest-neighbor graph over card embeddings
+    ExportKnnGraph {
+        /// Output format: graphml, json (adjacency list) or csv (edge list)
+        #[arg(long, default_value = "graphml")]
+        format: GraphFormat,
+        /// Nearest neighbors linked from each card, at most 50
+        #[arg(long, default_value_t = 10)]
+        k: i64,
+        /// Maximum number of cards in the graph, taken by ID, at most 5000
+        #[arg(long, default_value_t = 2000)]
+        max_cards: i64,
+        /// Distance metric: cosine, l2 or inner_product
+        #[arg(long, default_value = "cosine")]
+        metric: DistanceMetric,
+        /// Only include cards legal in this format
+        #[arg(long)]
+        legal_in: Option<String>,
+        /// Only include cards of this main type
+        #[arg(long)]
+        card_type: Option<String>,
+        /// File to write the graph to; standard output when omitted
+        #[arg(long)]
+        output: Option<PathBuf>,
+    },
+}
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -13,6 +110,16 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         std::env::set_var("RUST_LOG", "info");
     }
     dotenv().ok();
+    let command = Cli::parse().command.unwrap_or(Command::Serve);
+
+    // The MCP server sets up its own logging; batch commands log progress to stderr
+    if !matches!(command, Command::Serve) {
+        tracing_subscriber::fmt()
+            .with_env_filter(EnvFilter::from_default_env())
+            .with_writer(std::io::stderr)
+            .with_ansi(false)
+            .init();
+    }
 
     let database_url = std::env::var("DATABASE_URL").expect("DATABASE_URL must be set");
     let pool = match PgPoolOptions::new()
@@ -31,10 +138,195 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     };
 
     let card_repo = CardRepository::new(pool.clone());
-    let card_service = CardService::new(card_repo.clone());
-    let app_state = AppState { card_service };
+    let mut card_service = CardService::new(card_repo.clone());
+    match embeddings::from_env() {
+        Ok(Some(embedder)) => {
+            tracing::info!("Using embedding model {}", embedder.model_id());
+            card_service = card_service.with_embedder(embedder);
+        }
+        Ok(None) => tracing::info!("No embedder configured, semantic search is disabled"),
+        Err(err) => {
+            tracing::error!("Failed to load embedder: {}", err);
+            std::process::exit(1);
+        }
+    }
+
+    let (vector_backend, memory_index) = match ann::from_env() {
+        Ok(config) => config,
+        Err(err) => {
+            tracing::error!("Invalid vector backend configuration: {}", err);
+            std::process::exit(1);
+        }
+    };
+    // Only commands that run vector similarity queries need the index
+    let runs_vector_queries = matches!(
+        command,
+        Command::Serve | Command::EvaluateSimilarity { .. } | Command::ExportKnnGraph { .. }
+    );
+    let use_memory_index = runs_vector_queries
+        && match vector_backend {
+            VectorBackend::Pgvector => false,
+            VectorBackend::Memory => true,
+            VectorBackend::Auto => !card_repo
+                .vector_extension_installed()
+                .await
+                .map_err(|e| e.to_string())?,
+        };
+    if use_memory_index {
+        let index = card_service
+            .build_memory_index(memory_index)
+            .await
+            .map_err(|e| e.to_string())?;
+        tracing::info!(
+            "Serving vector queries from an in-memory {:?} index of {} embeddings",
+            memory_index,
+            index.len()
+        );
+        card_service = card_service.with_memory_index(index);
+    }
+
+    match command {
+        Command::Serve => {
+            let admin_tools = std::env::var("MANAQL_ADMIN_TOOLS")
+                .map(|value| matches!(value.as_str(), "1" | "true"))
+                .unwrap_or(false);
+            if admin_tools {
+                tracing::info!("Admin tools are enabled");
+            }
+            let app_state = AppState {
+                card_service,
+                deck_service: DeckService::new(card_repo),
+                admin_tools,
+            };
+
+            McpServer::start_stdio(app_state).await?;
+        }
+        Command::BackfillEmbeddings {
+            mode,
+            batch_size,
+            after_id,
+            max_cards,
+            named_space,
+        } => {
+            let options = BackfillOptions {
+                mode,
+                batch_size,
+                after_id,
+                max_cards,
+                named_space,
+            };
+            let progress = card_service
+                .backfill_embeddings(&options, |progress| {
+                    tracing::info!(
+                        "Embedded {}/{} cards ({} failed, last id {})",
+                        progress.processed,
+                        progress.total,
+                        progress.failed,
+                        progress.last_id.unwrap_or_default()
+                    );
+                })
+                .await
+                .map_err(|e| e.to_string())?;
 
-    McpServer::start_stdio(app_state).await?;
+            if progress.done {
+                tracing::info!(
+                    "Backfill complete: {} cards updated, {} failed",
+                    progress.updated,
+                    progress.failed
+                );
+            } else if let Some(last_id) = progress.last_id {
+                tracing::info!(
+                    "Backfill stopped after {} cards; resume with --after-id {}",
+                    progress.processed,
+                    last_id
+                );
+            }
+        }
+        Command::EvaluateSimilarity {
+            golden,
+            k,
+            embedding_space,
+            metric,
+            ef_search,
+            probes,
+            output,
+        } => {
+            let golden = GoldenSet::load(&golden).map_err(|e| e.to_string())?;
+            let options = VectorSearchOptions {
+                space: embedding_space,
+                metric,
+                ef_search,
+                probes,
+            };
+            let report = card_service
+                .evaluate_similarity(&golden, k, &options)
+                .await
+                .map_err(|e| e.to_string())?;
+
+            for query in report
+                .queries
+                .iter()
+                .filter(|query| !query.missed.is_empty())
+            {
+                tracing::info!(
+                    "{}: recall {:.3}, missed {}",
+                    query.card,
+                    query.recall,
+                    query.missed.join(", ")
+                );
+            }
+            if !report.skipped.is_empty() {
+                tracing::warn!("Skipped {} query cards", report.skipped.len());
+            }
+            println!(
+                "queries={} k={} recall@{}={:.4} mrr={:.4} ndcg@{}={:.4}",
+                report.query_count,
+                report.k,
+                report.k,
+                report.recall,
+                report.mrr,
+                report.k,
+                report.ndcg
+            );
+            if let Some(path) = output {
+                std::fs::write(&path, serde_json::to_string_pretty(&report)?)?;
+                tracing::info!("Wrote evaluation report to {}", path.display());
+            }
+        }
+        Command::ExportKnnGraph {
+            format,
+            k,
+            max_cards,
+            metric,
+            legal_in,
+            card_type,
+            output,
+        } => {
+            let filters = SearchFilters {
+                legal_in,
+                card_type,
+                ..Default::default()
+            };
+            let graph = card_service
+                .knn_graph(&filters, k, max_cards, metric)
+                .await
+                .map_err(|e| e.to_string())?;
+            let content = graph.render(format);
+
+            match output {
+                Some(path) => {
+                    std::fs::write(&path, content)?;
+                    tracing::info!(
+                        "Wrote {} nodes and {} edges to {}",
+                        graph.nodes.len(),
+                        graph.edges.len(),
+                        path.display()
+                    );
+                }
+                None => print!("{}", content),
+            }
+        }
+    }
 
     Ok(())
 }
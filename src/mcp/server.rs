@@ -1,15 +1,70 @@
 use rmcp::{
-    handler::server::{router::tool::ToolRouter, tool::Parameters},
+    handler::server::{router::tool::ToolRouter, wrapper::Parameters},
     model::ErrorData as McpError,
     model::*,
     service::RequestContext,
     tool, tool_handler, tool_router, RoleServer, ServerHandler,
 };
 use serde_json::json;
-use std::future::Future;
 
+use crate::cards::backfill::BackfillOptions;
+use crate::cards::duplicates::DuplicateReport;
+use crate::cards::mcp::{
+    format_card_fields, project_card, BackfillEmbeddingsRequest, BackfillEmbeddingsResponse,
+    CardClusterOutput, CardCountResponse, CardField, CardPoint, ClusterCardsResponse,
+    CompareCardsResponse, CreateVectorIndexRequest, DropVectorIndexRequest,
+    EmbeddingSpacesResponse, FindNearDuplicatesRequest, HybridSearchMatch, HybridSearchResponse,
+    IndexMethod, KeywordCount, KnnGraphResponse, ProjectCardsResponse, SearchCardsResponse,
+    SemanticSearchResponse, SimilarCardMatch, SimilarCardsResponse, StrictlyBetterCard,
+    StrictlyBetterCardsResponse, SuggestReplacementsResponse, VectorIndexResponse,
+    VectorIndexesResponse,
+};
+use crate::cards::service::{DEFAULT_DUPLICATE_NEIGHBORS, DEFAULT_DUPLICATE_THRESHOLD};
+use crate::cards::{
+    CardResponse, ReplacementConstraints, SimilarCardsQuery, VectorIndexMethod, VectorSearchOptions,
+};
+use crate::decks::mcp::{DeckEntryOutput, ParseDecklistResponse, SectionCount};
+use crate::decks::{DeckSection, DeckValidation};
 use crate::AppState;
 
+/// Builds a successful tool result carrying both the text rendering and the
+/// structured JSON form of the output
+fn structured_result<T: serde::Serialize>(
+    text: String,
+    output: &T,
+) -> Result<CallToolResult, McpError> {
+    let structured = serde_json::to_value(output).map_err(|e| {
+        McpError::internal_error(
+            "serialization_error",
+            Some(json!({ "error": e.to_string() })),
+        )
+    })?;
+    let mut result = CallToolResult::success(vec![Content::text(text)]);
+    result.structured_content = Some(structured);
+    Ok(result)
+}
+
+/// Builds a resource read result holding the JSON form of `value`
+fn json_resource<T: serde::Serialize>(
+    uri: String,
+    value: &T,
+) -> Result<ReadResourceResult, McpError> {
+    let text = serde_json::to_string_pretty(value).map_err(|e| {
+        McpError::internal_error(
+            "serialization_error",
+            Some(json!({ "error": e.to_string() })),
+        )
+    })?;
+    Ok(ReadResourceResult {
+        contents: vec![ResourceContents::TextResourceContents {
+            uri,
+            mime_type: Some("application/json".to_string()),
+            text,
+            meta: None,
+        }],
+    })
+}
+
 pub struct McpServer {
     tool_router: ToolRouter<Self>,
     app_state: AppState,
@@ -18,8 +73,12 @@ pub struct McpServer {
 #[tool_router]
 impl McpServer {
     pub fn new(app_state: AppState) -> Self {
+        let mut tool_router = Self::tool_router();
+        if app_state.admin_tools {
+            tool_router += Self::admin_tool_router();
+        }
         Self {
-            tool_router: Self::tool_router(),
+            tool_router,
             app_state,
         }
     }
@@ -30,7 +89,8 @@ impl McpServer {
 
     // Tool implementations
     #[tool(
-        description = "Search for cards using filters (name, type) and optional query for additional filtering across multiple fields with pagination support"
+        description = "Search for cards using filters (name, type, mana pips/devotion, permanents only, format legality, color identity, max cmc, reserved list, card list) and optional query for additional filtering across multiple fields with pagination support",
+        output_schema = rmcp::handler::server::tool::cached_schema_for_type::<SearchCardsResponse>()
     )]
     pub async fn search_cards(
         &self,
@@ -38,6 +98,10 @@ impl McpServer {
     ) -> Result<CallToolResult, McpError> {
         let limit = request.limit.unwrap_or(10);
         let offset = request.offset.unwrap_or(0);
+        let fields = CardField::selected(
+            request.response_fields.as_deref(),
+            CardField::SEARCH_DEFAULT,
+        );
 
         match self
             .app_state
@@ -51,6 +115,7 @@ impl McpServer {
             .await
         {
             Ok(cards) => {
+                let cards: Vec<CardResponse> = cards.into_iter().map(CardResponse::from).collect();
                 let result = if cards.is_empty() {
                     let filter_desc = if request.query.is_some() {
                         format!(
@@ -62,144 +127,1208 @@ impl McpServer {
                     };
                     format!("No cards found {}", filter_desc)
                 } else {
-                    let card_names: Vec<String> =
-                        cards.iter().map(|card| card.name.clone()).collect();
                     let filter_desc = if request.query.is_some() {
                         "with filters and query"
                     } else {
                         "with filters"
                     };
-                    format!(
-                        "Found {} cards {} (offset: {}, limit: {}): {}",
+                    let header = format!(
+                        "Found {} cards {} (offset: {}, limit: {})",
                         cards.len(),
                         filter_desc,
                         offset,
-                        limit,
-                        card_names.join(", ")
-                    )
+                        limit
+                    );
+                    if fields == [CardField::Name] {
+                        let card_names: Vec<&str> =
+                            cards.iter().map(|card| card.name.as_str()).collect();
+                        format!("{}: {}", header, card_names.join(", "))
+                    } else {
+                        let card_details: Vec<String> = cards
+                            .iter()
+                            .map(|card| format!("- {}", format_card_fields(card, fields)))
+                            .collect();
+                        format!("{}:\n{}", header, card_details.join("\n"))
+                    }
+                };
+                let output = SearchCardsResponse {
+                    count: cards.len(),
+                    offset,
+                    limit,
+                    cards: cards
+                        .iter()
+                        .map(|card| project_card(card, fields))
+                        .collect(),
                 };
-                Ok(CallToolResult::success(vec![Content::text(result)]))
+                structured_result(result, &output)
             }
             Err(e) => {
                 tracing::error!("Error searching cards: {:?}", e);
-                Err(McpError::resource_not_found(
-                    "internal_server_error",
-                    Some(json!({ "error": e.to_string() })),
-                ))
+                Err(e.into())
             }
         }
     }
 
-    #[tool(description = "Get a specific card by ID")]
+    #[tool(
+        description = "Get a specific card by ID",
+        output_schema = rmcp::handler::server::tool::cached_schema_for_type::<CardResponse>()
+    )]
     pub async fn get_card_by_id(
         &self,
         Parameters(request): Parameters<crate::cards::mcp::GetCardByIdRequest>,
     ) -> Result<CallToolResult, McpError> {
         match self.app_state.card_service.get_card_by_id(request.id).await {
             Ok(card) => {
-                let result = format!(
-                    "Card: {} (ID: {}, Type: {})",
-                    card.name, card.id, card.main_type
-                );
-                Ok(CallToolResult::success(vec![Content::text(result)]))
+                let card = CardResponse::from(card);
+                let (result, fields) = match request
+                    .response_fields
+                    .as_deref()
+                    .filter(|fields| !fields.is_empty())
+                {
+                    None => (
+                        format!(
+                            "Card: {} (ID: {}, Type: {})",
+                            card.name, card.id, card.main_type
+                        ),
+                        CardField::GET_DEFAULT,
+                    ),
+                    Some(fields) => (
+                        format!("Card: {}", format_card_fields(&card, fields)),
+                        fields,
+                    ),
+                };
+                structured_result(result, &project_card(&card, fields))
             }
             Err(e) => {
                 tracing::error!("Error getting card by ID: {:?}", e);
-                Err(McpError::resource_not_found(
-                    "internal_server_error",
-                    Some(json!({ "error": e.to_string() })),
-                ))
+                Err(e.into())
             }
         }
     }
 
-    #[tool(description = "Get total number of cards in database")]
+    #[tool(
+        description = "Get total number of cards in database",
+        output_schema = rmcp::handler::server::tool::cached_schema_for_type::<CardCountResponse>()
+    )]
     pub async fn get_card_count(&self) -> Result<CallToolResult, McpError> {
         match self.app_state.card_service.get_card_count().await {
             Ok(count) => {
                 let result = format!("Total cards in database: {}", count);
-                Ok(CallToolResult::success(vec![Content::text(result)]))
+                structured_result(result, &CardCountResponse { count })
             }
             Err(e) => {
                 tracing::error!("Error getting card count: {:?}", e);
-                Err(McpError::resource_not_found(
-                    "internal_server_error",
-                    Some(json!({ "error": e.to_string() })),
-                ))
+                Err(e.into())
             }
         }
     }
 
-    #[tool(description = "Find similar cards using vector similarity search based on card characteristics like type, mana cost, function, etc.")]
+    #[tool(
+        description = "Find similar cards using vector similarity search based on card characteristics like type, mana cost, function, etc. Several seed cards can be combined and negative examples steered away from, and results can be diversified. Optional filters (format legality, color identity, main type, max cmc, reserved list, ...) constrain the neighbors",
+        output_schema = rmcp::handler::server::tool::cached_schema_for_type::<SimilarCardsResponse>()
+    )]
     pub async fn find_similar_cards(
         &self,
         Parameters(request): Parameters<crate::cards::mcp::FindSimilarCardsRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        let fields = CardField::selected(
+            request.response_fields.as_deref(),
+            CardField::SIMILAR_DEFAULT,
+        );
+        let mut query = SimilarCardsQuery {
+            card_names: vec![request.card_name.clone()],
+            negative_card_names: request.negative_card_names.clone().unwrap_or_default(),
+            limit: request.limit.unwrap_or(10) as i64,
+            ..Default::default()
+        };
+        query
+            .card_names
+            .extend(request.additional_card_names.iter().flatten().cloned());
+        if let Some(negative_weight) = request.negative_weight {
+            query.negative_weight = negative_weight;
+        }
+        if let Some(diversity) = request.diversity {
+            query.diversity = diversity;
+        }
+        query.min_similarity = request.min_similarity;
+        query.vector_options = VectorSearchOptions {
+            space: request.embedding_space.clone(),
+            metric: request.metric.unwrap_or_default(),
+            ef_search: request.ef_search,
+            probes: request.probes,
+        };
+
+        match self
+            .app_state
+            .card_service
+            .find_similar_cards(&query, request.filters.as_ref())
+            .await
+        {
+            Ok(neighbors) => {
+                let seeds = query
+                    .card_names
+                    .iter()
+                    .map(|name| format!("'{}'", name))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                let negatives = if query.negative_card_names.is_empty() {
+                    String::new()
+                } else {
+                    format!(" but not {}", query.negative_card_names.join(", "))
+                };
+
+                let mut card_details = Vec::new();
+                let mut matches = Vec::new();
+                for neighbor in neighbors {
+                    let card = CardResponse::from(neighbor.card);
+                    card_details.push(format!(
+                        "- {} | Similarity: {:.3}",
+                        format_card_fields(&card, fields),
+                        neighbor.similarity
+                    ));
+                    matches.push(SimilarCardMatch {
+                        card: project_card(&card, fields),
+                        similarity: neighbor.similarity,
+                    });
+                }
+
+                let result = if card_details.is_empty() {
+                    match query.min_similarity {
+                        Some(min_similarity) => format!(
+                            "No cards with similarity of at least {} to {}{}",
+                            min_similarity, seeds, negatives
+                        ),
+                        None => format!("No similar cards found for {}{}", seeds, negatives),
+                    }
+                } else {
+                    format!(
+                        "Found {} similar cards to {}{}:\n{}",
+                        card_details.len(),
+                        seeds,
+                        negatives,
+                        card_details.join("\n")
+                    )
+                };
+                let output = SimilarCardsResponse {
+                    card_names: query.card_names,
+                    negative_card_names: query.negative_card_names,
+                    embedding_space: query.vector_options.space,
+                    metric: query.vector_options.metric,
+                    min_similarity: query.min_similarity,
+                    matches,
+                };
+                structured_result(result, &output)
+            }
+            Err(e) => {
+                tracing::error!("Error finding similar cards: {:?}", e);
+                Err(e.into())
+            }
+        }
+    }
+
+    #[tool(
+        description = "Search for cards matching a natural-language description (e.g. \"cheap artifact removal that exiles\") by embedding the text and finding the nearest card embeddings",
+        output_schema = rmcp::handler::server::tool::cached_schema_for_type::<SemanticSearchResponse>()
+    )]
+    pub async fn semantic_search(
+        &self,
+        Parameters(request): Parameters<crate::cards::mcp::SemanticSearchRequest>,
     ) -> Result<CallToolResult, McpError> {
         let limit = request.limit.unwrap_or(10);
+        let fields = CardField::selected(
+            request.response_fields.as_deref(),
+            CardField::SIMILAR_DEFAULT,
+        );
 
         match self
             .app_state
             .card_service
-            .find_similar_cards(&request.card_name, Some(limit))
+            .semantic_search(
+                &request.query,
+                Some(limit),
+                &VectorSearchOptions {
+                    space: request.embedding_space.clone(),
+                    ..Default::default()
+                },
+            )
             .await
         {
             Ok(cards) => {
+                let cards: Vec<CardResponse> = cards.into_iter().map(CardResponse::from).collect();
                 let result = if cards.is_empty() {
-                    format!("No similar cards found for '{}'", request.card_name)
+                    format!("No cards found for '{}'", request.query)
                 } else {
                     let card_details: Vec<String> = cards
                         .iter()
-                        .map(|card| {
-                            let mut details = vec![
-                                format!("{} ({})", card.name, card.main_type)
-                            ];
-                            
-                            if let Some(cmc) = card.cmc {
-                                details.push(format!("CMC: {}", cmc));
-                            }
-                            if let Some(ref mana_cost) = card.mana_cost {
-                                details.push(format!("Cost: {}", mana_cost));
-                            }
-                            if let Some(ref colors) = card.colors {
-                                if !colors.is_empty() {
-                                    details.push(format!("Colors: {}", colors.join(", ")));
-                                }
-                            }
-                            if let Some(ref keywords) = card.keywords {
-                                if !keywords.is_empty() {
-                                    details.push(format!("Keywords: {}", keywords.join(", ")));
-                                }
-                            }
-                            if let Some(ref power) = card.power {
-                                if let Some(ref toughness) = card.toughness {
-                                    details.push(format!("{}/{}", power, toughness));
-                                }
-                            }
-                            if let Some(ref oracle_text) = card.oracle_text {
-                                if !oracle_text.is_empty() {
-                                    details.push(format!("Text: {}", oracle_text));
-                                }
-                            }
-                            
-                            format!("- {}", details.join(" | "))
-                        })
+                        .map(|card| format!("- {}", format_card_fields(card, fields)))
                         .collect();
 
                     format!(
-                        "Found {} similar cards to '{}':\n{}",
+                        "Found {} cards matching '{}':\n{}",
                         cards.len(),
+                        request.query,
+                        card_details.join("\n")
+                    )
+                };
+                let output = SemanticSearchResponse {
+                    query: request.query,
+                    cards: cards
+                        .iter()
+                        .map(|card| project_card(card, fields))
+                        .collect(),
+                };
+                structured_result(result, &output)
+            }
+            Err(e) => {
+                tracing::error!("Error running semantic search: {:?}", e);
+                Err(e.into())
+            }
+        }
+    }
+
+    #[tool(
+        description = "Search for cards by combining full-text matches on name, type line and oracle text with vector similarity to the query, fused with reciprocal rank fusion. Catches both exact terms like \"Treasure\" and paraphrases",
+        output_schema = rmcp::handler::server::tool::cached_schema_for_type::<HybridSearchResponse>()
+    )]
+    pub async fn hybrid_search(
+        &self,
+        Parameters(request): Parameters<crate::cards::mcp::HybridSearchRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        let fields = CardField::selected(
+            request.response_fields.as_deref(),
+            CardField::SIMILAR_DEFAULT,
+        );
+
+        match self
+            .app_state
+            .card_service
+            .hybrid_search(
+                &request.query,
+                request.limit,
+                request.lexical_weight,
+                request.vector_weight,
+                request.rank_constant,
+                &VectorSearchOptions {
+                    space: request.embedding_space.clone(),
+                    ..Default::default()
+                },
+            )
+            .await
+        {
+            Ok(matches) => {
+                let rank = |rank: Option<usize>| {
+                    rank.map(|r| format!("#{}", r))
+                        .unwrap_or_else(|| "-".to_string())
+                };
+                let mut card_details = Vec::new();
+                let mut output_matches = Vec::new();
+                for hybrid_match in matches {
+                    let card = CardResponse::from(hybrid_match.card);
+                    card_details.push(format!(
+                        "- {} | Score: {:.4} (lexical {}, vector {})",
+                        format_card_fields(&card, fields),
+                        hybrid_match.score,
+                        rank(hybrid_match.lexical_rank),
+                        rank(hybrid_match.vector_rank)
+                    ));
+                    output_matches.push(HybridSearchMatch {
+                        card: project_card(&card, fields),
+                        score: hybrid_match.score,
+                        lexical_rank: hybrid_match.lexical_rank,
+                        vector_rank: hybrid_match.vector_rank,
+                    });
+                }
+
+                let result = if card_details.is_empty() {
+                    format!("No cards found for '{}'", request.query)
+                } else {
+                    format!(
+                        "Found {} cards matching '{}':\n{}",
+                        card_details.len(),
+                        request.query,
+                        card_details.join("\n")
+                    )
+                };
+                let output = HybridSearchResponse {
+                    query: request.query,
+                    matches: output_matches,
+                };
+                structured_result(result, &output)
+            }
+            Err(e) => {
+                tracing::error!("Error running hybrid search: {:?}", e);
+                Err(e.into())
+            }
+        }
+    }
+
+    #[tool(
+        description = "Find cards that are strictly better than a given card (same main type and rules text with lower mana value, higher power/toughness or extra keywords) and functional reprints of it",
+        output_schema = rmcp::handler::server::tool::cached_schema_for_type::<StrictlyBetterCardsResponse>()
+    )]
+    pub async fn find_strictly_better_cards(
+        &self,
+        Parameters(request): Parameters<crate::cards::mcp::FindStrictlyBetterCardsRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        match self
+            .app_state
+            .card_service
+            .find_strictly_better_cards(&request.card_name, request.limit)
+            .await
+        {
+            Ok(better) => {
+                let mut sections = Vec::new();
+                if better.strictly_better.is_empty() {
+                    sections.push(format!(
+                        "No strictly better cards found for '{}'",
+                        request.card_name
+                    ));
+                } else {
+                    let lines: Vec<String> = better
+                        .strictly_better
+                        .iter()
+                        .map(|(card, reasons)| format!("- {} ({})", card.name, reasons.join(", ")))
+                        .collect();
+                    sections.push(format!(
+                        "Strictly better than '{}':\n{}",
+                        request.card_name,
+                        lines.join("\n")
+                    ));
+                }
+                if better.functional_reprints.is_empty() {
+                    sections.push(format!(
+                        "No functional reprints found for '{}'",
+                        request.card_name
+                    ));
+                } else {
+                    let lines: Vec<String> = better
+                        .functional_reprints
+                        .iter()
+                        .map(|card| format!("- {}", card.name))
+                        .collect();
+                    sections.push(format!(
+                        "Functional reprints of '{}':\n{}",
                         request.card_name,
+                        lines.join("\n")
+                    ));
+                }
+
+                let fields = CardField::SIMILAR_DEFAULT;
+                let output = StrictlyBetterCardsResponse {
+                    card_name: request.card_name,
+                    strictly_better: better
+                        .strictly_better
+                        .into_iter()
+                        .map(|(card, reasons)| StrictlyBetterCard {
+                            card: project_card(&card.into(), fields),
+                            reasons,
+                        })
+                        .collect(),
+                    functional_reprints: better
+                        .functional_reprints
+                        .into_iter()
+                        .map(|card| project_card(&card.into(), fields))
+                        .collect(),
+                };
+                structured_result(sections.join("\n\n"), &output)
+            }
+            Err(e) => {
+                tracing::error!("Error finding strictly better cards: {:?}", e);
+                Err(e.into())
+            }
+        }
+    }
+
+    #[tool(
+        description = "Suggest replacements for a card that is banned, on the Reserved List or outside a deck's colors: the closest cards by embedding similarity that are legal in the format, fit the color identity and optionally avoid the Reserved List",
+        output_schema = rmcp::handler::server::tool::cached_schema_for_type::<SuggestReplacementsResponse>()
+    )]
+    pub async fn suggest_replacements(
+        &self,
+        Parameters(request): Parameters<crate::cards::mcp::SuggestReplacementsRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        let fields = CardField::selected(
+            request.response_fields.as_deref(),
+            CardField::SIMILAR_DEFAULT,
+        );
+        let constraints = ReplacementConstraints {
+            format: request.format.clone(),
+            color_identity: request.color_identity.clone(),
+            exclude_reserved: request.exclude_reserved.unwrap_or(false),
+            same_card_type: request.same_card_type.unwrap_or(true),
+        };
+
+        match self
+            .app_state
+            .card_service
+            .suggest_replacements(
+                &request.card_name,
+                &constraints,
+                request.limit.unwrap_or(10) as i64,
+            )
+            .await
+        {
+            Ok(replacements) => {
+                let mut card_details = Vec::new();
+                let mut matches = Vec::new();
+                for suggestion in replacements.suggestions {
+                    let card = CardResponse::from(suggestion.card);
+                    card_details.push(format!(
+                        "- {} | Similarity: {:.3}",
+                        format_card_fields(&card, fields),
+                        suggestion.similarity
+                    ));
+                    matches.push(SimilarCardMatch {
+                        card: project_card(&card, fields),
+                        similarity: suggestion.similarity,
+                    });
+                }
+
+                let mut result = if replacements.reasons.is_empty() {
+                    format!("'{}' already fits the constraints.", replacements.card.name)
+                } else {
+                    format!(
+                        "'{}' does not fit: {}.",
+                        replacements.card.name,
+                        replacements.reasons.join("; ")
+                    )
+                };
+                if card_details.is_empty() {
+                    result.push_str(" No replacements found.");
+                } else {
+                    result.push_str(&format!(
+                        " Closest replacements:\n{}",
                         card_details.join("\n")
+                    ));
+                }
+
+                let output = SuggestReplacementsResponse {
+                    card_name: replacements.card.name,
+                    reasons: replacements.reasons,
+                    replacements: matches,
+                };
+                structured_result(result, &output)
+            }
+            Err(e) => {
+                tracing::error!("Error suggesting replacements: {:?}", e);
+                Err(e.into())
+            }
+        }
+    }
+
+    #[tool(
+        description = "Explain why two cards are similar: their embedding similarity alongside shared type line words, keywords and colors, the mana value difference and oracle text phrases both cards contain",
+        output_schema = rmcp::handler::server::tool::cached_schema_for_type::<CompareCardsResponse>()
+    )]
+    pub async fn compare_cards(
+        &self,
+        Parameters(request): Parameters<crate::cards::mcp::CompareCardsRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        let fields = CardField::selected(
+            request.response_fields.as_deref(),
+            CardField::SIMILAR_DEFAULT,
+        );
+
+        match self
+            .app_state
+            .card_service
+            .compare_cards(&request.first_card_name, &request.second_card_name)
+            .await
+        {
+            Ok(comparison) => {
+                let first = CardResponse::from(comparison.first);
+                let second = CardResponse::from(comparison.second);
+                let explanation = comparison.explanation;
+                let list = |values: &[String]| {
+                    if values.is_empty() {
+                        "none".to_string()
+                    } else {
+                        values.join(", ")
+                    }
+                };
+
+                let mut lines = vec![
+                    format!("- {}", format_card_fields(&first, fields)),
+                    format!("- {}", format_card_fields(&second, fields)),
+                    String::new(),
+                    match explanation.embedding_similarity {
+                        Some(similarity) => format!("Embedding similarity: {:.3}", similarity),
+                        None => "Embedding similarity: unavailable (missing embedding)".to_string(),
+                    },
+                    format!("Shared types: {}", list(&explanation.type_line.shared)),
+                    format!("Shared keywords: {}", list(&explanation.keywords.shared)),
+                    format!("Shared colors: {}", list(&explanation.colors.shared)),
+                    format!("Mana value difference: {:+}", explanation.cmc_delta),
+                ];
+                if explanation.shared_phrases.is_empty() {
+                    lines.push("Shared oracle text: none".to_string());
+                } else {
+                    lines.push("Shared oracle text:".to_string());
+                    lines.extend(
+                        explanation
+                            .shared_phrases
+                            .iter()
+                            .map(|phrase| format!("  - \"{}\"", phrase)),
+                    );
+                }
+
+                let output = CompareCardsResponse {
+                    first: project_card(&first, fields),
+                    second: project_card(&second, fields),
+                    explanation,
+                };
+                structured_result(lines.join("\n"), &output)
+            }
+            Err(e) => {
+                tracing::error!("Error comparing cards: {:?}", e);
+                Err(e.into())
+            }
+        }
+    }
+
+    #[tool(
+        description = "List the named embedding spaces (one per embedding model) with their dimension and number of embedded cards, for choosing the embedding_space of find_similar_cards",
+        output_schema = rmcp::handler::server::tool::cached_schema_for_type::<EmbeddingSpacesResponse>()
+    )]
+    pub async fn list_embedding_spaces(&self) -> Result<CallToolResult, McpError> {
+        match self.app_state.card_service.list_embedding_spaces().await {
+            Ok(spaces) => {
+                let result = if spaces.is_empty() {
+                    "No named embedding spaces - similarity search uses the card table's embedding column".to_string()
+                } else {
+                    let details: Vec<String> = spaces
+                        .iter()
+                        .map(|space| {
+                            format!(
+                                "- {} ({} dimensions, {} cards)",
+                                space.model_id, space.dimension, space.card_count
+                            )
+                        })
+                        .collect();
+                    format!(
+                        "Found {} embedding spaces:\n{}",
+                        spaces.len(),
+                        details.join("\n")
                     )
                 };
-                Ok(CallToolResult::success(vec![Content::text(result)]))
+                structured_result(result, &EmbeddingSpacesResponse { spaces })
             }
             Err(e) => {
-                tracing::error!("Error finding similar cards: {:?}", e);
-                Err(McpError::resource_not_found(
-                    "internal_server_error",
-                    Some(json!({ "error": e.to_string() })),
-                ))
+                tracing::error!("Error listing embedding spaces: {:?}", e);
+                Err(e.into())
+            }
+        }
+    }
+
+    #[tool(
+        description = "Cluster the embeddings of a filtered card set (e.g. all Pauper-legal blue cards) with k-means to discover mechanical themes, returning each cluster's representative cards and most common keywords",
+        output_schema = rmcp::handler::server::tool::cached_schema_for_type::<ClusterCardsResponse>()
+    )]
+    pub async fn cluster_cards(
+        &self,
+        Parameters(request): Parameters<crate::cards::mcp::ClusterCardsRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        let fields = CardField::selected(
+            request.response_fields.as_deref(),
+            CardField::SIMILAR_DEFAULT,
+        );
+        let representatives = request.representatives.unwrap_or(5);
+        let keyword_limit = request.keywords.unwrap_or(5);
+
+        match self
+            .app_state
+            .card_service
+            .cluster_cards(
+                &request.filters,
+                request.clusters.unwrap_or(8),
+                request.max_cards.unwrap_or(2000),
+                request.seed.unwrap_or(0),
+            )
+            .await
+        {
+            Ok(clusters) => {
+                let card_count = clusters.iter().map(|cluster| cluster.cards.len()).sum();
+                let mut sections = Vec::new();
+                let mut outputs = Vec::new();
+                for (index, cluster) in clusters.into_iter().enumerate() {
+                    let size = cluster.cards.len();
+                    let cohesion = cluster
+                        .cards
+                        .iter()
+                        .map(|member| member.similarity)
+                        .sum::<f64>()
+                        / size as f64;
+                    let keywords: Vec<KeywordCount> = cluster
+                        .keywords
+                        .into_iter()
+                        .take(keyword_limit)
+                        .map(|(keyword, count)| KeywordCount { keyword, count })
+                        .collect();
+                    let cards: Vec<CardResponse> = cluster
+                        .cards
+                        .into_iter()
+                        .take(representatives)
+                        .map(|member| CardResponse::from(member.card))
+                        .collect();
+
+                    let keyword_summary = if keywords.is_empty() {
+                        "no keywords".to_string()
+                    } else {
+                        keywords
+                            .iter()
+                            .map(|k| format!("{} ({})", k.keyword, k.count))
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    };
+                    let card_details: Vec<String> = cards
+                        .iter()
+                        .map(|card| format!("  - {}", format_card_fields(card, fields)))
+                        .collect();
+                    sections.push(format!(
+                        "Cluster {} - {} cards, cohesion {:.3}, keywords: {}\n{}",
+                        index + 1,
+                        size,
+                        cohesion,
+                        keyword_summary,
+                        card_details.join("\n")
+                    ));
+
+                    outputs.push(CardClusterOutput {
+                        size,
+                        cohesion,
+                        keywords,
+                        representatives: cards
+                            .iter()
+                            .map(|card| project_card(card, fields))
+                            .collect(),
+                    });
+                }
+
+                let result = if outputs.is_empty() {
+                    "No cards with embeddings match the filters".to_string()
+                } else {
+                    format!(
+                        "Clustered {} cards into {} clusters:\n\n{}",
+                        card_count,
+                        outputs.len(),
+                        sections.join("\n\n")
+                    )
+                };
+                let output = ClusterCardsResponse {
+                    card_count,
+                    clusters: outputs,
+                };
+                structured_result(result, &output)
+            }
+            Err(e) => {
+                tracing::error!("Error clustering cards: {:?}", e);
+                Err(e.into())
+            }
+        }
+    }
+
+    #[tool(
+        description = "Project the embeddings of a filtered card set (e.g. all Pauper-legal cards) onto their first two principal components, returning 2D coordinates with each card's name and type for plotting a format's card space",
+        output_schema = rmcp::handler::server::tool::cached_schema_for_type::<ProjectCardsResponse>()
+    )]
+    pub async fn project_cards(
+        &self,
+        Parameters(request): Parameters<crate::cards::mcp::ProjectCardsRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        match self
+            .app_state
+            .card_service
+            .project_cards(&request.filters, request.max_cards.unwrap_or(2000))
+            .await
+        {
+            Ok(projection) => {
+                let points: Vec<CardPoint> = projection
+                    .cards
+                    .into_iter()
+                    .map(|(card, [x, y])| CardPoint {
+                        id: card.id,
+                        main_type: card.main_type.as_str().to_string(),
+                        name: card.name,
+                        type_line: card.type_line,
+                        color_identity: card.color_identity.unwrap_or_default(),
+                        cmc: card.cmc,
+                        x,
+                        y,
+                    })
+                    .collect();
+
+                let result = if points.is_empty() {
+                    "No cards with embeddings match the filters".to_string()
+                } else {
+                    let [first, second] = projection.explained_variance;
+                    let lines: Vec<String> = points
+                        .iter()
+                        .take(50)
+                        .map(|point| {
+                            format!(
+                                "- {} ({}): ({:.3}, {:.3})",
+                                point.name, point.main_type, point.x, point.y
+                            )
+                        })
+                        .collect();
+                    let mut result = format!(
+                        "Projected {} cards; the axes explain {:.1}% and {:.1}% of the variance:\n{}",
+                        points.len(),
+                        first * 100.0,
+                        second * 100.0,
+                        lines.join("\n")
+                    );
+                    if points.len() > lines.len() {
+                        result.push_str(&format!(
+                            "\n... and {} more in the structured output",
+                            points.len() - lines.len()
+                        ));
+                    }
+                    result
+                };
+                let output = ProjectCardsResponse {
+                    card_count: points.len(),
+                    explained_variance: projection.explained_variance,
+                    points,
+                };
+                structured_result(result, &output)
+            }
+            Err(e) => {
+                tracing::error!("Error projecting cards: {:?}", e);
+                Err(e.into())
+            }
+        }
+    }
+
+    #[tool(
+        description = "Export the k-nearest-neighbor graph over card embeddings (optionally filtered) as GraphML, a JSON adjacency list or a CSV edge list, for use in graph tools such as Gephi or networkx",
+        output_schema = rmcp::handler::server::tool::cached_schema_for_type::<KnnGraphResponse>()
+    )]
+    pub async fn export_knn_graph(
+        &self,
+        Parameters(request): Parameters<crate::cards::mcp::ExportKnnGraphRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        let format = request.format.unwrap_or_default();
+        let filters = request.filters.unwrap_or_default();
+
+        match self
+            .app_state
+            .card_service
+            .knn_graph(
+                &filters,
+                request.k.unwrap_or(10),
+                request.max_cards.unwrap_or(2000),
+                request.metric.unwrap_or_default(),
+            )
+            .await
+        {
+            Ok(graph) => {
+                let output = KnnGraphResponse {
+                    format,
+                    mime_type: format.mime_type().to_string(),
+                    node_count: graph.nodes.len(),
+                    edge_count: graph.edges.len(),
+                    content: graph.render(format),
+                };
+                let summary = format!(
+                    "Exported a kNN graph with {} nodes and {} edges as {}; the graph is in the structured content",
+                    output.node_count,
+                    output.edge_count,
+                    format.as_str()
+                );
+                structured_result(summary, &output)
+            }
+            Err(e) => {
+                tracing::error!("Error exporting kNN graph: {:?}", e);
+                Err(e.into())
+            }
+        }
+    }
+
+    #[tool(
+        description = "Parse a decklist (MTG Arena export, Moxfield/Archidekt plain text, MTGO .dek XML or CSV) into its main deck, sideboard, commander and companion sections, matching each line to a card",
+        output_schema = rmcp::handler::server::tool::cached_schema_for_type::<ParseDecklistResponse>()
+    )]
+    pub async fn parse_decklist(
+        &self,
+        Parameters(request): Parameters<crate::decks::mcp::ParseDecklistRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        match self
+            .app_state
+            .deck_service
+            .parse_deck(&request.decklist, request.format.unwrap_or_default())
+            .await
+        {
+            Ok(deck) => {
+                let sections: Vec<SectionCount> = [
+                    DeckSection::Commander,
+                    DeckSection::Companion,
+                    DeckSection::Main,
+                    DeckSection::Sideboard,
+                    DeckSection::Maybeboard,
+                ]
+                .into_iter()
+                .map(|section| SectionCount {
+                    section,
+                    count: deck.count(section),
+                })
+                .filter(|section| section.count > 0)
+                .collect();
+
+                let mut result = format!(
+                    "Parsed deck{}: {}",
+                    deck.name
+                        .as_ref()
+                        .map(|name| format!(" \"{}\"", name))
+                        .unwrap_or_default(),
+                    if sections.is_empty() {
+                        "no cards".to_string()
+                    } else {
+                        sections
+                            .iter()
+                            .map(|section| format!("{} {}", section.count, section.section))
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    }
+                );
+                for entry in &deck.entries {
+                    result.push_str(&format!(
+                        "\n- {} {} [{}]",
+                        entry.line.quantity, entry.card.name, entry.line.section
+                    ));
+                }
+                for line in &deck.unresolved {
+                    result.push_str(&format!(
+                        "\nUnknown card on line {}: {}",
+                        line.line_number, line.name
+                    ));
+                }
+                for error in &deck.errors {
+                    result.push_str(&format!(
+                        "\nCould not read line {}: {} ({})",
+                        error.line_number, error.line, error.message
+                    ));
+                }
+
+                let output = ParseDecklistResponse {
+                    name: deck.name,
+                    sections,
+                    entries: deck
+                        .entries
+                        .into_iter()
+                        .map(|entry| DeckEntryOutput {
+                            quantity: entry.line.quantity,
+                            name: entry.card.name,
+                            card_id: entry.card.id,
+                            section: entry.line.section,
+                            set_code: entry.line.set_code,
+                            collector_number: entry.line.collector_number,
+                            line_number: entry.line.line_number,
+                        })
+                        .collect(),
+                    unresolved: deck.unresolved,
+                    errors: deck.errors,
+                };
+                structured_result(result, &output)
+            }
+            Err(e) => {
+                tracing::error!("Error parsing decklist: {:?}", e);
+                Err(e.into())
+            }
+        }
+    }
+
+    #[tool(
+        description = "Check a decklist against a format's deck construction rules: banned, restricted and not-legal cards, copy limits (with basic land and \"any number of cards named\" exceptions), deck size and sideboard size. In commander formats also checks commander eligibility, partner/background/friends forever pairing and color identity. Returns each violation, per card and for the deck as a whole",
+        output_schema = rmcp::handler::server::tool::cached_schema_for_type::<DeckValidation>()
+    )]
+    pub async fn validate_deck(
+        &self,
+        Parameters(request): Parameters<crate::decks::mcp::ValidateDeckRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        match self
+            .app_state
+            .deck_service
+            .validate_deck(
+                &request.decklist,
+                request.decklist_format.unwrap_or_default(),
+                &request.format,
+            )
+            .await
+        {
+            Ok(validation) => {
+                let mut result = format!(
+                    "Deck is {} in {} ({} cards, {} in sideboard)",
+                    if validation.legal {
+                        "legal"
+                    } else {
+                        "not legal"
+                    },
+                    validation.format,
+                    validation.deck_size,
+                    validation.sideboard_size
+                );
+                if !validation.commanders.is_empty() {
+                    result.push_str(&format!(
+                        "\nCommanders: {} (color identity: {})",
+                        validation.commanders.join(", "),
+                        match validation.color_identity.as_deref() {
+                            Some([]) | None => "colorless".to_string(),
+                            Some(colors) => colors.join(""),
+                        }
+                    ));
+                }
+                for violation in &validation.deck_violations {
+                    result.push_str(&format!("\n- {}", violation.message));
+                }
+                for card in &validation.card_violations {
+                    for violation in &card.violations {
+                        result.push_str(&format!("\n- {}", violation.message));
+                    }
+                }
+                structured_result(result, &validation)
+            }
+            Err(e) => {
+                tracing::error!("Error validating deck: {:?}", e);
+                Err(e.into())
+            }
+        }
+    }
+}
+
+/// Database administration tools, only registered when `MANAQL_ADMIN_TOOLS` is set
+#[tool_router(router = admin_tool_router)]
+impl McpServer {
+    #[tool(
+        description = "Admin: compute embeddings for cards missing them (or stale/all) with the configured embedder, in resumable batches. Sends progress notifications when the request carries a progress token",
+        output_schema = rmcp::handler::server::tool::cached_schema_for_type::<BackfillEmbeddingsResponse>()
+    )]
+    pub async fn backfill_embeddings(
+        &self,
+        Parameters(request): Parameters<BackfillEmbeddingsRequest>,
+        context: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, McpError> {
+        let options = BackfillOptions {
+            mode: request.mode.unwrap_or_default(),
+            batch_size: request.batch_size.unwrap_or(100),
+            after_id: request.after_id,
+            max_cards: Some(request.max_cards.unwrap_or(1000)),
+            named_space: request.named_space.unwrap_or(false),
+        };
+        let progress_token = context.meta.get_progress_token();
+        // Notifications go through a single task so they arrive in batch order
+        let (progress_tx, mut progress_rx) = tokio::sync::mpsc::unbounded_channel();
+        let peer = context.peer.clone();
+        let notifier = tokio::spawn(async move {
+            while let Some(notification) = progress_rx.recv().await {
+                if let Err(e) = peer.notify_progress(notification).await {
+                    tracing::warn!("Failed to send progress notification: {:?}", e);
+                }
+            }
+        });
+
+        let backfill = self
+            .app_state
+            .card_service
+            .backfill_embeddings(&options, |progress| {
+                if let Some(progress_token) = &progress_token {
+                    let notification = ProgressNotificationParam {
+                        progress_token: progress_token.clone(),
+                        progress: progress.processed as f64,
+                        total: Some(progress.total as f64),
+                        message: Some(format!("{} cards failed", progress.failed)),
+                    };
+                    // The receiver only stops once the sender is dropped below
+                    let _ = progress_tx.send(notification);
+                }
+            })
+            .await;
+        drop(progress_tx);
+        if let Err(e) = notifier.await {
+            tracing::warn!("Progress notification task failed: {:?}", e);
+        }
+
+        match backfill {
+            Ok(progress) => {
+                let mut result = format!(
+                    "Embedded {} of {} cards ({} updated, {} failed)",
+                    progress.processed, progress.total, progress.updated, progress.failed
+                );
+                if !progress.done {
+                    if let Some(last_id) = progress.last_id {
+                        result.push_str(&format!(
+                            ". More cards remain - call again with after_id {}",
+                            last_id
+                        ));
+                    }
+                }
+                let output = BackfillEmbeddingsResponse {
+                    mode: options.mode,
+                    progress,
+                };
+                structured_result(result, &output)
+            }
+            Err(e) => {
+                tracing::error!("Error backfilling embeddings: {:?}", e);
+                Err(e.into())
+            }
+        }
+    }
+
+    #[tool(
+        description = "Admin: scan the whole card pool for near-duplicate pairs (embedding similarity above a threshold) and group them into families of functional variants. The result is also served as the manaql://near-duplicates resource",
+        output_schema = rmcp::handler::server::tool::cached_schema_for_type::<DuplicateReport>()
+    )]
+    pub async fn find_near_duplicates(
+        &self,
+        Parameters(request): Parameters<FindNearDuplicatesRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        match self
+            .app_state
+            .card_service
+            .find_near_duplicates(
+                request.threshold.unwrap_or(DEFAULT_DUPLICATE_THRESHOLD),
+                request.max_neighbors.unwrap_or(DEFAULT_DUPLICATE_NEIGHBORS),
+            )
+            .await
+        {
+            Ok(report) => {
+                let family_lines: Vec<String> = report
+                    .families
+                    .iter()
+                    .take(20)
+                    .map(|family| {
+                        format!(
+                            "- {} (min similarity {:.3})",
+                            family
+                                .cards
+                                .iter()
+                                .map(|card| card.name.as_str())
+                                .collect::<Vec<_>>()
+                                .join(", "),
+                            family.min_similarity
+                        )
+                    })
+                    .collect();
+                let mut result = format!(
+                    "Found {} near-duplicate pairs in {} families (threshold {})",
+                    report.pair_count,
+                    report.families.len(),
+                    report.threshold
+                );
+                if !family_lines.is_empty() {
+                    result.push_str(&format!(":\n{}", family_lines.join("\n")));
+                }
+                if report.families.len() > family_lines.len() {
+                    result.push_str(
+                        "\n... see the manaql://near-duplicates resource for all families",
+                    );
+                }
+                structured_result(result, report.as_ref())
+            }
+            Err(e) => {
+                tracing::error!("Error finding near-duplicate cards: {:?}", e);
+                Err(e.into())
+            }
+        }
+    }
+
+    #[tool(
+        description = "Admin: list the HNSW and IVFFlat vector indexes on card embeddings with their definitions and sizes",
+        output_schema = rmcp::handler::server::tool::cached_schema_for_type::<VectorIndexesResponse>()
+    )]
+    pub async fn list_vector_indexes(&self) -> Result<CallToolResult, McpError> {
+        match self.app_state.card_service.list_vector_indexes().await {
+            Ok(indexes) => {
+                let result = if indexes.is_empty() {
+                    "No vector indexes - similarity queries scan every embedding".to_string()
+                } else {
+                    let details: Vec<String> = indexes
+                        .iter()
+                        .map(|index| {
+                            format!(
+                                "- {} ({}, {} bytes): {}",
+                                index.name, index.method, index.size_bytes, index.definition
+                            )
+                        })
+                        .collect();
+                    format!(
+                        "Found {} vector indexes:\n{}",
+                        indexes.len(),
+                        details.join("\n")
+                    )
+                };
+                structured_result(result, &VectorIndexesResponse { indexes })
+            }
+            Err(e) => {
+                tracing::error!("Error listing vector indexes: {:?}", e);
+                Err(e.into())
+            }
+        }
+    }
+
+    #[tool(
+        description = "Admin: build an HNSW or IVFFlat index on card embeddings for a distance metric (cosine, l2, inner_product) so similarity queries stop scanning every row",
+        output_schema = rmcp::handler::server::tool::cached_schema_for_type::<VectorIndexResponse>()
+    )]
+    pub async fn create_vector_index(
+        &self,
+        Parameters(request): Parameters<CreateVectorIndexRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        let method = match request.method {
+            IndexMethod::Hnsw => VectorIndexMethod::Hnsw {
+                m: request.m.unwrap_or(16),
+                ef_construction: request.ef_construction.unwrap_or(64),
+            },
+            IndexMethod::Ivfflat => VectorIndexMethod::IvfFlat {
+                lists: request.lists.unwrap_or(100),
+            },
+        };
+        let metric = request.metric.unwrap_or_default();
+
+        match self
+            .app_state
+            .card_service
+            .create_vector_index(method, metric)
+            .await
+        {
+            Ok(name) => structured_result(
+                format!(
+                    "Vector index {} ready ({} with {})",
+                    name,
+                    method.as_str(),
+                    metric.operator_class()
+                ),
+                &VectorIndexResponse { name },
+            ),
+            Err(e) => {
+                tracing::error!("Error creating vector index: {:?}", e);
+                Err(e.into())
+            }
+        }
+    }
+
+    #[tool(
+        description = "Admin: drop a vector index on card embeddings by name",
+        output_schema = rmcp::handler::server::tool::cached_schema_for_type::<VectorIndexResponse>()
+    )]
+    pub async fn drop_vector_index(
+        &self,
+        Parameters(request): Parameters<DropVectorIndexRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        match self
+            .app_state
+            .card_service
+            .drop_vector_index(&request.name)
+            .await
+        {
+            Ok(()) => structured_result(
+                format!("Dropped vector index {}", request.name),
+                &VectorIndexResponse { name: request.name },
+            ),
+            Err(e) => {
+                tracing::error!("Error dropping vector index: {:?}", e);
+                Err(e.into())
             }
         }
     }
@@ -209,14 +1338,14 @@ impl McpServer {
 impl ServerHandler for McpServer {
     fn get_info(&self) -> ServerInfo {
         ServerInfo {
-            protocol_version: ProtocolVersion::V_2024_11_05,
+            protocol_version: ProtocolVersion::V_2025_06_18,
             capabilities: ServerCapabilities::builder()
                 .enable_prompts()
                 .enable_resources()
                 .enable_tools()
                 .build(),
             server_info: Implementation::from_build_env(),
-            instructions: Some("ManaQL MCP Server - Provides tools and prompts for Magic: The Gathering card data. Tools: search_cards, get_card_by_id, get_card_count, find_similar_cards (vector similarity search).".to_string()),
+            instructions: Some("ManaQL MCP Server - Provides tools and prompts for Magic: The Gathering card data. Tools: search_cards, get_card_by_id, get_card_count, find_similar_cards (vector similarity search), list_embedding_spaces, semantic_search (natural-language vector search), hybrid_search (full-text + vector rank fusion), find_strictly_better_cards, suggest_replacements (legal alternatives to banned/off-color cards), compare_cards (explain why two cards are similar), cluster_cards (k-means themes over a filtered card set), project_cards (2D PCA coordinates for plotting), export_knn_graph (kNN graph as GraphML/JSON/CSV), parse_decklist (Arena/Moxfield/Archidekt text, MTGO .dek or CSV decklists), validate_deck (format legality with per-card violations, including Commander color identity and pairing). Admin tools when MANAQL_ADMIN_TOOLS is set: backfill_embeddings, find_near_duplicates, list_vector_indexes, create_vector_index, drop_vector_index.".to_string()),
         }
     }
 
@@ -225,8 +1354,24 @@ impl ServerHandler for McpServer {
         _request: Option<PaginatedRequestParam>,
         _: RequestContext<RoleServer>,
     ) -> Result<ListResourcesResult, McpError> {
+        let mut resources = vec![self._create_resource_text("manaql://cards", "mtg-cards")];
+        // Listed once an admin has run find_near_duplicates
+        if self
+            .app_state
+            .card_service
+            .near_duplicates()
+            .await
+            .is_some()
+        {
+            resources.push(
+                self._create_resource_text(
+                    "manaql://near-duplicates",
+                    "near-duplicate-card-families",
+                ),
+            );
+        }
         Ok(ListResourcesResult {
-            resources: vec![self._create_resource_text("manaql://cards", "mtg-cards")],
+            resources,
             next_cursor: None,
         })
     }
@@ -243,6 +1388,18 @@ impl ServerHandler for McpServer {
                     contents: vec![ResourceContents::text(cards_info, uri)],
                 })
             }
+            "manaql://near-duplicates" => {
+                match self.app_state.card_service.near_duplicates().await {
+                    Some(report) => json_resource(uri, report.as_ref()),
+                    None => Err(McpError::resource_not_found(
+                        "resource_not_found",
+                        Some(json!({
+                            "uri": uri,
+                            "error": "The near-duplicate report has not been computed yet; an admin can run find_near_duplicates",
+                        })),
+                    )),
+                }
+            }
             _ => Err(McpError::resource_not_found(
                 "resource_not_found",
                 Some(json!({
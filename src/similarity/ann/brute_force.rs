@@ -0,0 +1,120 @@
+use super::{exact_search, AnnIndex, Neighbor};
+use crate::cards::DistanceMetric;
+use std::collections::HashMap;
+
+/// Exact nearest-neighbor search by scanning every vector
+pub struct BruteForceIndex {
+    dimension: usize,
+    ids: Vec<i32>,
+    vectors: Vec<Vec<f32>>,
+    positions: HashMap<i32, usize>,
+}
+
+impl BruteForceIndex {
+    pub fn new(dimension: usize, vectors: Vec<(i32, Vec<f32>)>) -> Self {
+        let (ids, vectors): (Vec<i32>, Vec<Vec<f32>>) = vectors.into_iter().unzip();
+        let positions = ids
+            .iter()
+            .enumerate()
+            .map(|(position, &id)| (id, position))
+            .collect();
+        Self {
+            dimension,
+            ids,
+            vectors,
+            positions,
+        }
+    }
+}
+
+impl AnnIndex for BruteForceIndex {
+    fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    fn dimension(&self) -> usize {
+        self.dimension
+    }
+
+    fn vector(&self, id: i32) -> Option<&[f32]> {
+        self.positions
+            .get(&id)
+            .map(|&position| self.vectors[position].as_slice())
+    }
+
+    fn search(
+        &self,
+        query: &[f32],
+        k: usize,
+        metric: DistanceMetric,
+        _ef_search: Option<usize>,
+        filter: &dyn Fn(i32) -> bool,
+    ) -> Vec<Neighbor> {
+        exact_search(
+            self.ids
+                .iter()
+                .copied()
+                .zip(self.vectors.iter().map(Vec::as_slice)),
+            query,
+            k,
+            metric,
+            filter,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn index() -> BruteForceIndex {
+        BruteForceIndex::new(
+            2,
+            vec![
+                (1, vec![1.0, 0.0]),
+                (2, vec![3.0, 0.3]),
+                (3, vec![0.0, 1.0]),
+                (4, vec![-1.0, 0.0]),
+            ],
+        )
+    }
+
+    fn ids(neighbors: &[Neighbor]) -> Vec<i32> {
+        neighbors.iter().map(|neighbor| neighbor.id).collect()
+    }
+
+    #[test]
+    fn orders_by_cosine_distance() {
+        let neighbors = index().search(&[2.0, 0.0], 4, DistanceMetric::Cosine, None, &|_| true);
+        assert_eq!(ids(&neighbors), vec![1, 2, 3, 4]);
+        assert!(neighbors[0].distance.abs() < 1e-6);
+    }
+
+    #[test]
+    fn orders_by_l2_distance() {
+        let neighbors = index().search(&[2.0, 0.0], 4, DistanceMetric::L2, None, &|_| true);
+        assert_eq!(ids(&neighbors), vec![1, 2, 3, 4]);
+        assert!((neighbors[0].distance - 1.0).abs() < 1e-6);
+        assert!((neighbors[3].distance - 3.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn orders_by_negative_inner_product() {
+        // The longer vector wins under inner product although it is not the
+        // closest in direction
+        let neighbors = index().search(&[2.0, 0.0], 4, DistanceMetric::InnerProduct, None, &|_| {
+            true
+        });
+        assert_eq!(ids(&neighbors), vec![2, 1, 3, 4]);
+        assert!((neighbors[0].distance + 6.0).abs() < 1e-6);
+        assert!((neighbors[3].distance - 2.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn applies_filter_and_limit() {
+        let neighbors = index().search(&[2.0, 0.0], 2, DistanceMetric::L2, None, &|id| id != 1);
+        assert_eq!(ids(&neighbors), vec![2, 3]);
+        assert_eq!(index().vector(3), Some([0.0, 1.0].as_slice()));
+        assert_eq!(index().vector(5), None);
+    }
+}
@@ -0,0 +1,380 @@
+use super::{exact_search, AnnIndex, Neighbor};
+use crate::cards::DistanceMetric;
+use crate::similarity::kmeans::SplitMix64;
+use std::cmp::{Ordering, Reverse};
+use std::collections::{BinaryHeap, HashMap, HashSet};
+
+/// Links per node on the upper layers (twice as many on the bottom layer),
+/// the same default as pgvector's `m`
+pub(super) const DEFAULT_M: usize = 16;
+/// Candidate list size while inserting, the same default as pgvector's
+/// `ef_construction`
+pub(super) const DEFAULT_EF_CONSTRUCTION: usize = 64;
+/// Candidate list size while searching, the same default as pgvector's
+/// `hnsw.ef_search`
+const DEFAULT_EF_SEARCH: usize = 40;
+
+/// A node with its distance to the vector being searched for, ordered by
+/// distance
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Candidate {
+    distance: f32,
+    node: usize,
+}
+
+impl Eq for Candidate {}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Candidate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.distance
+            .total_cmp(&other.distance)
+            .then(self.node.cmp(&other.node))
+    }
+}
+
+/// Approximate nearest-neighbor search over a hierarchical navigable small
+/// world graph. The graph is built for one metric; searches with another
+/// metric, or whose filter leaves fewer than `k` of the graph's candidates,
+/// fall back to an exact scan.
+pub struct HnswIndex {
+    dimension: usize,
+    ids: Vec<i32>,
+    vectors: Vec<Vec<f32>>,
+    positions: HashMap<i32, usize>,
+    metric: DistanceMetric,
+    m: usize,
+    /// Neighbors of each node on each of its layers, bottom layer first
+    links: Vec<Vec<Vec<usize>>>,
+    entry_point: Option<usize>,
+}
+
+impl HnswIndex {
+    pub fn new(
+        dimension: usize,
+        vectors: Vec<(i32, Vec<f32>)>,
+        metric: DistanceMetric,
+        m: usize,
+        ef_construction: usize,
+    ) -> Self {
+        let (ids, vectors): (Vec<i32>, Vec<Vec<f32>>) = vectors.into_iter().unzip();
+        let positions = ids
+            .iter()
+            .enumerate()
+            .map(|(position, &id)| (id, position))
+            .collect();
+        let m = m.max(2);
+        let mut index = Self {
+            dimension,
+            ids,
+            vectors,
+            positions,
+            metric,
+            m,
+            links: Vec::new(),
+            entry_point: None,
+        };
+
+        // Layers are drawn from a fixed seed so the graph is reproducible
+        let mut rng = SplitMix64(0x5eed);
+        let level_multiplier = 1.0 / (m as f64).ln();
+        for node in 0..index.vectors.len() {
+            let uniform = rng.next_f64().max(f64::MIN_POSITIVE);
+            let level = (-uniform.ln() * level_multiplier).floor() as usize;
+            index.insert(node, level, ef_construction.max(m));
+        }
+        index
+    }
+
+    fn distance(&self, query: &[f32], node: usize) -> f32 {
+        self.metric.distance(query, &self.vectors[node])
+    }
+
+    fn max_links(&self, layer: usize) -> usize {
+        if layer == 0 {
+            self.m * 2
+        } else {
+            self.m
+        }
+    }
+
+    fn insert(&mut self, node: usize, level: usize, ef_construction: usize) {
+        self.links.push(vec![Vec::new(); level + 1]);
+        let Some(entry) = self.entry_point else {
+            self.entry_point = Some(node);
+            return;
+        };
+
+        let query = self.vectors[node].clone();
+        let top = self.links[entry].len() - 1;
+        let mut entry_points = vec![Candidate {
+            distance: self.distance(&query, entry),
+            node: entry,
+        }];
+        for layer in (level + 1..=top).rev() {
+            entry_points = self.search_layer(&query, &entry_points, 1, layer);
+        }
+
+        for layer in (0..=level.min(top)).rev() {
+            let candidates = self.search_layer(&query, &entry_points, ef_construction, layer);
+            let neighbors: Vec<usize> = candidates
+                .iter()
+                .take(self.m)
+                .map(|candidate| candidate.node)
+                .collect();
+            let max_links = self.max_links(layer);
+            for &neighbor in &neighbors {
+                self.links[neighbor][layer].push(node);
+                if self.links[neighbor][layer].len() > max_links {
+                    self.prune(neighbor, layer, max_links);
+                }
+            }
+            self.links[node][layer] = neighbors;
+            entry_points = candidates;
+        }
+
+        if level > top {
+            self.entry_point = Some(node);
+        }
+    }
+
+    /// Keeps only the `max_links` closest neighbors of a node on a layer
+    fn prune(&mut self, node: usize, layer: usize, max_links: usize) {
+        let mut candidates: Vec<Candidate> = self.links[node][layer]
+            .iter()
+            .map(|&neighbor| Candidate {
+                distance: self
+                    .metric
+                    .distance(&self.vectors[node], &self.vectors[neighbor]),
+                node: neighbor,
+            })
+            .collect();
+        candidates.sort();
+        candidates.truncate(max_links);
+        self.links[node][layer] = candidates
+            .into_iter()
+            .map(|candidate| candidate.node)
+            .collect();
+    }
+
+    /// Best-first search of one layer from the entry points, returning up to
+    /// `ef` nodes closest to `query`, nearest first
+    fn search_layer(
+        &self,
+        query: &[f32],
+        entry_points: &[Candidate],
+        ef: usize,
+        layer: usize,
+    ) -> Vec<Candidate> {
+        let mut visited: HashSet<usize> = entry_points.iter().map(|c| c.node).collect();
+        let mut candidates: BinaryHeap<Reverse<Candidate>> =
+            entry_points.iter().copied().map(Reverse).collect();
+        let mut results: BinaryHeap<Candidate> = entry_points.iter().copied().collect();
+        while results.len() > ef {
+            results.pop();
+        }
+
+        while let Some(Reverse(current)) = candidates.pop() {
+            let furthest = results.peek().map(|c| c.distance).unwrap_or(f32::INFINITY);
+            if current.distance > furthest && results.len() >= ef {
+                break;
+            }
+            for &neighbor in &self.links[current.node][layer] {
+                if !visited.insert(neighbor) {
+                    continue;
+                }
+                let candidate = Candidate {
+                    distance: self.distance(query, neighbor),
+                    node: neighbor,
+                };
+                let furthest = results.peek().map(|c| c.distance).unwrap_or(f32::INFINITY);
+                if results.len() < ef || candidate.distance < furthest {
+                    candidates.push(Reverse(candidate));
+                    results.push(candidate);
+                    if results.len() > ef {
+                        results.pop();
+                    }
+                }
+            }
+        }
+
+        results.into_sorted_vec()
+    }
+
+    fn exact_search(
+        &self,
+        query: &[f32],
+        k: usize,
+        metric: DistanceMetric,
+        filter: &dyn Fn(i32) -> bool,
+    ) -> Vec<Neighbor> {
+        exact_search(
+            self.ids
+                .iter()
+                .copied()
+                .zip(self.vectors.iter().map(Vec::as_slice)),
+            query,
+            k,
+            metric,
+            filter,
+        )
+    }
+}
+
+impl AnnIndex for HnswIndex {
+    fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    fn dimension(&self) -> usize {
+        self.dimension
+    }
+
+    fn vector(&self, id: i32) -> Option<&[f32]> {
+        self.positions
+            .get(&id)
+            .map(|&position| self.vectors[position].as_slice())
+    }
+
+    fn search(
+        &self,
+        query: &[f32],
+        k: usize,
+        metric: DistanceMetric,
+        ef_search: Option<usize>,
+        filter: &dyn Fn(i32) -> bool,
+    ) -> Vec<Neighbor> {
+        if metric != self.metric {
+            return self.exact_search(query, k, metric, filter);
+        }
+        let Some(entry) = self.entry_point else {
+            return Vec::new();
+        };
+
+        let mut entry_points = vec![Candidate {
+            distance: self.distance(query, entry),
+            node: entry,
+        }];
+        for layer in (1..self.links[entry].len()).rev() {
+            entry_points = self.search_layer(query, &entry_points, 1, layer);
+        }
+        let ef = ef_search.unwrap_or(DEFAULT_EF_SEARCH).max(k);
+        let neighbors: Vec<Neighbor> = self
+            .search_layer(query, &entry_points, ef, 0)
+            .into_iter()
+            .filter(|candidate| filter(self.ids[candidate.node]))
+            .take(k)
+            .map(|candidate| Neighbor {
+                id: self.ids[candidate.node],
+                distance: candidate.distance,
+            })
+            .collect();
+
+        // A selective filter can reject most of the graph's candidates
+        if neighbors.len() < k && neighbors.len() < self.len() {
+            return self.exact_search(query, k, metric, filter);
+        }
+        neighbors
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::BruteForceIndex;
+    use super::*;
+
+    fn random_vectors(count: usize, dimension: usize, seed: u64) -> Vec<(i32, Vec<f32>)> {
+        let mut rng = SplitMix64(seed);
+        (0..count)
+            .map(|id| {
+                let vector = (0..dimension)
+                    .map(|_| rng.next_f64() as f32 * 2.0 - 1.0)
+                    .collect();
+                (id as i32, vector)
+            })
+            .collect()
+    }
+
+    fn ids(neighbors: &[Neighbor]) -> Vec<i32> {
+        neighbors.iter().map(|neighbor| neighbor.id).collect()
+    }
+
+    fn indexes(count: usize, dimension: usize) -> (HnswIndex, BruteForceIndex) {
+        let vectors = random_vectors(count, dimension, 42);
+        (
+            HnswIndex::new(
+                dimension,
+                vectors.clone(),
+                DistanceMetric::Cosine,
+                DEFAULT_M,
+                DEFAULT_EF_CONSTRUCTION,
+            ),
+            BruteForceIndex::new(dimension, vectors),
+        )
+    }
+
+    #[test]
+    fn recall_matches_brute_force() {
+        let (hnsw, exact) = indexes(1000, 16);
+        let k = 10;
+        let mut found = 0;
+        let queries = random_vectors(50, 16, 7);
+        for (_, query) in &queries {
+            let expected: HashSet<i32> = exact
+                .search(query, k, DistanceMetric::Cosine, None, &|_| true)
+                .into_iter()
+                .map(|neighbor| neighbor.id)
+                .collect();
+            let neighbors = hnsw.search(query, k, DistanceMetric::Cosine, Some(64), &|_| true);
+            assert_eq!(neighbors.len(), k);
+            found += neighbors
+                .iter()
+                .filter(|neighbor| expected.contains(&neighbor.id))
+                .count();
+        }
+        let recall = found as f64 / (queries.len() * k) as f64;
+        assert!(recall >= 0.95, "recall@{k} was {recall}");
+    }
+
+    #[test]
+    fn selective_filter_falls_back_to_exact_search() {
+        let (hnsw, exact) = indexes(1000, 8);
+        let query = &random_vectors(1, 8, 7)[0].1;
+        let filter = |id: i32| id % 97 == 0;
+        let expected = exact.search(query, 5, DistanceMetric::Cosine, None, &filter);
+        let neighbors = hnsw.search(query, 5, DistanceMetric::Cosine, None, &filter);
+        assert_eq!(neighbors.len(), 5);
+        assert_eq!(ids(&neighbors), ids(&expected));
+    }
+
+    #[test]
+    fn other_metrics_fall_back_to_exact_search() {
+        let (hnsw, exact) = indexes(500, 8);
+        let query = &random_vectors(1, 8, 7)[0].1;
+        for metric in [DistanceMetric::L2, DistanceMetric::InnerProduct] {
+            assert_eq!(
+                hnsw.search(query, 10, metric, None, &|_| true),
+                exact.search(query, 10, metric, None, &|_| true)
+            );
+        }
+    }
+
+    #[test]
+    fn empty_index_finds_nothing() {
+        let index = HnswIndex::new(4, Vec::new(), DistanceMetric::Cosine, DEFAULT_M, 64);
+        assert!(index
+            .search(
+                &[1.0, 0.0, 0.0, 0.0],
+                3,
+                DistanceMetric::Cosine,
+                None,
+                &|_| true
+            )
+            .is_empty());
+    }
+}
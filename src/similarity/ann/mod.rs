@@ -0,0 +1,217 @@
+//! In-process nearest-neighbor indexes over card embeddings, used instead of
+//! pgvector when the database lacks the `vector` extension.
+
+mod brute_force;
+mod hnsw;
+
+pub use brute_force::BruteForceIndex;
+pub use hnsw::HnswIndex;
+
+use crate::cards::DistanceMetric;
+use crate::error::Error;
+use std::str::FromStr;
+use std::sync::Arc;
+
+/// A vector found by an index with its distance to the query
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Neighbor {
+    pub id: i32,
+    pub distance: f32,
+}
+
+/// A nearest-neighbor index over vectors keyed by card ID
+pub trait AnnIndex: Send + Sync {
+    /// Number of indexed vectors
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Dimension of the indexed vectors
+    fn dimension(&self) -> usize;
+
+    /// The indexed vector of a card
+    fn vector(&self, id: i32) -> Option<&[f32]>;
+
+    /// The `k` vectors closest to `query` under `metric` whose IDs pass
+    /// `filter`, nearest first. `ef_search` sizes the candidate list of
+    /// approximate indexes; exact indexes ignore it.
+    fn search(
+        &self,
+        query: &[f32],
+        k: usize,
+        metric: DistanceMetric,
+        ef_search: Option<usize>,
+        filter: &dyn Fn(i32) -> bool,
+    ) -> Vec<Neighbor>;
+}
+
+/// Where vector queries run
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum VectorBackend {
+    /// pgvector if the `vector` extension is installed, in memory otherwise
+    #[default]
+    Auto,
+    Pgvector,
+    Memory,
+}
+
+impl FromStr for VectorBackend {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "auto" => Ok(VectorBackend::Auto),
+            "pgvector" => Ok(VectorBackend::Pgvector),
+            "memory" => Ok(VectorBackend::Memory),
+            other => Err(Error::BadRequest(format!(
+                "Unknown VECTOR_BACKEND '{}' (expected auto, pgvector or memory)",
+                other
+            ))),
+        }
+    }
+}
+
+/// Kind of in-memory index
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum AnnIndexKind {
+    /// Exact search, returning the same neighbors as pgvector without an index
+    #[default]
+    BruteForce,
+    /// Approximate search over an HNSW graph built for cosine distance
+    Hnsw,
+}
+
+impl FromStr for AnnIndexKind {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "brute_force" => Ok(AnnIndexKind::BruteForce),
+            "hnsw" => Ok(AnnIndexKind::Hnsw),
+            other => Err(Error::BadRequest(format!(
+                "Unknown MEMORY_INDEX '{}' (expected brute_force or hnsw)",
+                other
+            ))),
+        }
+    }
+}
+
+/// Reads the vector backend and in-memory index kind from `VECTOR_BACKEND`
+/// and `MEMORY_INDEX`
+pub fn from_env() -> Result<(VectorBackend, AnnIndexKind), Error> {
+    let backend = match std::env::var("VECTOR_BACKEND") {
+        Ok(value) => value.parse()?,
+        Err(_) => VectorBackend::default(),
+    };
+    let kind = match std::env::var("MEMORY_INDEX") {
+        Ok(value) => value.parse()?,
+        Err(_) => AnnIndexKind::default(),
+    };
+    Ok((backend, kind))
+}
+
+/// Builds an index of the given kind. Vectors whose dimension differs from the
+/// first one are skipped.
+pub fn build(kind: AnnIndexKind, vectors: Vec<(i32, Vec<f32>)>) -> Arc<dyn AnnIndex> {
+    let dimension = vectors.first().map(|(_, v)| v.len()).unwrap_or(0);
+    let (vectors, skipped): (Vec<_>, Vec<_>) = vectors
+        .into_iter()
+        .partition(|(_, vector)| vector.len() == dimension);
+    if !skipped.is_empty() {
+        tracing::warn!(
+            "Skipped {} embeddings whose dimension differs from {}",
+            skipped.len(),
+            dimension
+        );
+    }
+
+    match kind {
+        AnnIndexKind::BruteForce => Arc::new(BruteForceIndex::new(dimension, vectors)),
+        AnnIndexKind::Hnsw => Arc::new(HnswIndex::new(
+            dimension,
+            vectors,
+            DistanceMetric::Cosine,
+            hnsw::DEFAULT_M,
+            hnsw::DEFAULT_EF_CONSTRUCTION,
+        )),
+    }
+}
+
+/// Exact search over the given vectors, shared by the indexes
+fn exact_search<'a>(
+    vectors: impl Iterator<Item = (i32, &'a [f32])>,
+    query: &[f32],
+    k: usize,
+    metric: DistanceMetric,
+    filter: &dyn Fn(i32) -> bool,
+) -> Vec<Neighbor> {
+    let mut neighbors: Vec<Neighbor> = vectors
+        .filter(|(id, _)| filter(*id))
+        .map(|(id, vector)| Neighbor {
+            id,
+            distance: metric.distance(query, vector),
+        })
+        .collect();
+    let by_distance =
+        |a: &Neighbor, b: &Neighbor| a.distance.total_cmp(&b.distance).then(a.id.cmp(&b.id));
+    if neighbors.len() > k {
+        neighbors.select_nth_unstable_by(k, by_distance);
+        neighbors.truncate(k);
+    }
+    neighbors.sort_by(by_distance);
+    neighbors
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exact_search_breaks_ties_by_id() {
+        let vectors = [
+            (7, vec![0.0, 1.0]),
+            (3, vec![1.0, 0.0]),
+            (5, vec![0.0, -1.0]),
+            (1, vec![-1.0, 0.0]),
+        ];
+        let neighbors = exact_search(
+            vectors.iter().map(|(id, vector)| (*id, vector.as_slice())),
+            &[0.0, 0.0],
+            3,
+            DistanceMetric::L2,
+            &|_| true,
+        );
+        let ids: Vec<i32> = neighbors.iter().map(|neighbor| neighbor.id).collect();
+        assert_eq!(ids, vec![1, 3, 5]);
+    }
+
+    #[test]
+    fn build_skips_vectors_of_another_dimension() {
+        let index = build(
+            AnnIndexKind::BruteForce,
+            vec![
+                (1, vec![1.0, 0.0]),
+                (2, vec![1.0, 0.0, 0.0]),
+                (3, vec![0.0, 1.0]),
+            ],
+        );
+        assert_eq!(index.dimension(), 2);
+        assert_eq!(index.len(), 2);
+        assert!(index.vector(2).is_none());
+    }
+
+    #[test]
+    fn parses_backend_and_index_kind() {
+        assert_eq!(
+            "Memory".parse::<VectorBackend>().unwrap(),
+            VectorBackend::Memory
+        );
+        assert_eq!("hnsw".parse::<AnnIndexKind>().unwrap(), AnnIndexKind::Hnsw);
+        assert!(matches!(
+            "faiss".parse::<AnnIndexKind>(),
+            Err(Error::BadRequest(_))
+        ));
+    }
+}
@@ -0,0 +1,115 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// Default `k` constant from the original reciprocal rank fusion paper
+pub const DEFAULT_RRF_K: f64 = 60.0;
+
+/// One ranked result list to fuse, best first
+pub struct RankedList<'a, T> {
+    pub items: &'a [T],
+    pub weight: f64,
+}
+
+/// A fused result with its score and 1-based rank in each input list
+#[derive(Debug, Clone, PartialEq)]
+pub struct FusedItem<T> {
+    pub item: T,
+    pub score: f64,
+    pub ranks: Vec<Option<usize>>,
+}
+
+/// Combines ranked lists with weighted reciprocal rank fusion: each item
+/// scores `weight / (k + rank)` summed over the lists it appears in. Items are
+/// returned by descending score, ties keeping the order of first appearance.
+pub fn reciprocal_rank_fusion<T: Eq + Hash + Clone>(
+    lists: &[RankedList<'_, T>],
+    k: f64,
+) -> Vec<FusedItem<T>> {
+    let mut positions: HashMap<&T, usize> = HashMap::new();
+    let mut fused: Vec<FusedItem<T>> = Vec::new();
+
+    for (list_index, list) in lists.iter().enumerate() {
+        for (position, item) in list.items.iter().enumerate() {
+            let rank = position + 1;
+            let index = *positions.entry(item).or_insert_with(|| {
+                fused.push(FusedItem {
+                    item: item.clone(),
+                    score: 0.0,
+                    ranks: vec![None; lists.len()],
+                });
+                fused.len() - 1
+            });
+            let entry = &mut fused[index];
+            // Only the best rank of an item counts if a list repeats it
+            if entry.ranks[list_index].is_none() {
+                entry.ranks[list_index] = Some(rank);
+                entry.score += list.weight / (k + rank as f64);
+            }
+        }
+    }
+
+    fused.sort_by(|a, b| b.score.total_cmp(&a.score));
+    fused
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fuse(lists: &[(&[&'static str], f64)]) -> Vec<FusedItem<&'static str>> {
+        let lists: Vec<_> = lists
+            .iter()
+            .map(|(items, weight)| RankedList {
+                items,
+                weight: *weight,
+            })
+            .collect();
+        reciprocal_rank_fusion(&lists, DEFAULT_RRF_K)
+    }
+
+    fn order(fused: &[FusedItem<&'static str>]) -> Vec<&'static str> {
+        fused.iter().map(|fused| fused.item).collect()
+    }
+
+    #[test]
+    fn items_in_both_lists_outrank_items_in_one() {
+        let fused = fuse(&[(&["a", "b", "c"], 1.0), (&["c", "d"], 1.0)]);
+        assert_eq!(order(&fused), vec!["c", "a", "b", "d"]);
+        assert_eq!(fused[0].ranks, vec![Some(3), Some(1)]);
+        assert!((fused[0].score - (1.0 / 63.0 + 1.0 / 61.0)).abs() < 1e-12);
+    }
+
+    #[test]
+    fn items_missing_from_a_list_have_no_rank_in_it() {
+        let fused = fuse(&[(&["a"], 1.0), (&["b"], 1.0)]);
+        assert_eq!(fused[0].ranks, vec![Some(1), None]);
+        assert_eq!(fused[1].ranks, vec![None, Some(1)]);
+        assert_eq!(fused[0].score, fused[1].score);
+    }
+
+    #[test]
+    fn ties_keep_the_order_of_first_appearance() {
+        let fused = fuse(&[(&["a", "b"], 1.0), (&["b", "a"], 1.0)]);
+        assert_eq!(order(&fused), vec!["a", "b"]);
+        let fused = fuse(&[(&["x", "y"], 1.0), (&["y", "x"], 1.0)]);
+        assert_eq!(order(&fused), vec!["x", "y"]);
+    }
+
+    #[test]
+    fn weights_scale_each_lists_contribution() {
+        let fused = fuse(&[(&["a", "b"], 1.0), (&["b", "a"], 3.0)]);
+        assert_eq!(order(&fused), vec!["b", "a"]);
+        assert!((fused[0].score - (1.0 / 62.0 + 3.0 / 61.0)).abs() < 1e-12);
+
+        let fused = fuse(&[(&["a"], 0.0), (&["b"], 1.0)]);
+        assert_eq!(order(&fused), vec!["b", "a"]);
+        assert_eq!(fused[1].score, 0.0);
+    }
+
+    #[test]
+    fn repeated_items_only_count_their_best_rank() {
+        let fused = fuse(&[(&["a", "b", "a"], 1.0)]);
+        assert_eq!(fused[0].ranks, vec![Some(1)]);
+        assert!((fused[0].score - 1.0 / 61.0).abs() < 1e-12);
+    }
+}
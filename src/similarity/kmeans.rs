@@ -0,0 +1,242 @@
+use super::vector::{dot, normalize};
+
+/// Result of clustering a set of vectors
+#[derive(Debug, Clone)]
+pub struct Clustering {
+    /// Cluster index of each input vector
+    pub assignments: Vec<usize>,
+    /// Unit-length centroid of each cluster
+    pub centroids: Vec<Vec<f32>>,
+}
+
+/// Small deterministic PRNG (SplitMix64) so clusterings are reproducible for a
+/// given seed
+pub(crate) struct SplitMix64(pub(crate) u64);
+
+impl SplitMix64 {
+    pub(crate) fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform float in [0, 1)
+    pub(crate) fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+/// Index of the centroid with the highest dot product with `vector`
+fn nearest_centroid(vector: &[f32], centroids: &[Vec<f32>]) -> (usize, f32) {
+    centroids
+        .iter()
+        .enumerate()
+        .map(|(index, centroid)| (index, dot(vector, centroid)))
+        .max_by(|(_, a), (_, b)| a.total_cmp(b))
+        .unwrap_or((0, 0.0))
+}
+
+/// k-means++ seeding: each further centroid is drawn with probability
+/// proportional to the squared cosine distance to the nearest chosen one
+fn seed_centroids(vectors: &[Vec<f32>], k: usize, rng: &mut SplitMix64) -> Vec<Vec<f32>> {
+    let first = (rng.next_u64() % vectors.len() as u64) as usize;
+    let mut centroids = vec![vectors[first].clone()];
+
+    while centroids.len() < k {
+        let weights: Vec<f64> = vectors
+            .iter()
+            .map(|vector| {
+                let distance = (1.0 - nearest_centroid(vector, &centroids).1).max(0.0) as f64;
+                distance * distance
+            })
+            .collect();
+        let total: f64 = weights.iter().sum();
+
+        let next = if total > 0.0 {
+            let mut target = rng.next_f64() * total;
+            weights
+                .iter()
+                .position(|weight| {
+                    target -= weight;
+                    target < 0.0
+                })
+                .unwrap_or(vectors.len() - 1)
+        } else {
+            // Every vector coincides with a centroid; any choice is as good
+            (rng.next_u64() % vectors.len() as u64) as usize
+        };
+        centroids.push(vectors[next].clone());
+    }
+
+    centroids
+}
+
+/// Spherical k-means: clusters vectors by cosine similarity, with k-means++
+/// seeding. Stops when assignments no longer change or after `max_iterations`.
+/// `k` is capped at the number of vectors.
+pub fn spherical_kmeans(
+    vectors: &[&[f32]],
+    k: usize,
+    max_iterations: usize,
+    seed: u64,
+) -> Clustering {
+    let k = k.min(vectors.len());
+    if k == 0 {
+        return Clustering {
+            assignments: vec![0; vectors.len()],
+            centroids: Vec::new(),
+        };
+    }
+
+    let vectors: Vec<Vec<f32>> = vectors
+        .iter()
+        .map(|vector| {
+            let mut vector = vector.to_vec();
+            normalize(&mut vector);
+            vector
+        })
+        .collect();
+    let dimension = vectors[0].len();
+    let mut rng = SplitMix64(seed);
+    let mut centroids = seed_centroids(&vectors, k, &mut rng);
+    let mut assignments = vec![usize::MAX; vectors.len()];
+
+    for _ in 0..max_iterations.max(1) {
+        let mut changed = false;
+        for (vector, assignment) in vectors.iter().zip(assignments.iter_mut()) {
+            let (nearest, _) = nearest_centroid(vector, &centroids);
+            if *assignment != nearest {
+                *assignment = nearest;
+                changed = true;
+            }
+        }
+        if !changed {
+            break;
+        }
+
+        let mut sums = vec![vec![0.0f32; dimension]; k];
+        let mut sizes = vec![0usize; k];
+        for (vector, &assignment) in vectors.iter().zip(&assignments) {
+            sums[assignment]
+                .iter_mut()
+                .zip(vector)
+                .for_each(|(sum, value)| *sum += value);
+            sizes[assignment] += 1;
+        }
+
+        // Re-seed empty clusters with the vectors worst served by their
+        // centroids, a different one for each cluster
+        let mut worst_served: Vec<usize> = Vec::new();
+        if sizes.contains(&0) {
+            worst_served = (0..vectors.len()).collect();
+            worst_served.sort_by(|&a, &b| {
+                dot(&vectors[a], &centroids[assignments[a]])
+                    .total_cmp(&dot(&vectors[b], &centroids[assignments[b]]))
+            });
+        }
+        let mut worst_served = worst_served.into_iter();
+
+        for (cluster, mut sum) in sums.into_iter().enumerate() {
+            if sizes[cluster] == 0 {
+                if let Some(farthest) = worst_served.next() {
+                    sum = vectors[farthest].clone();
+                }
+            }
+            normalize(&mut sum);
+            centroids[cluster] = sum;
+        }
+    }
+
+    Clustering {
+        assignments,
+        centroids,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Three tight groups around the coordinate axes
+    fn separable() -> Vec<Vec<f32>> {
+        let mut rng = SplitMix64(7);
+        let mut vectors = Vec::new();
+        for axis in 0..3 {
+            for _ in 0..10 {
+                let mut vector: Vec<f32> = (0..3).map(|_| rng.next_f64() as f32 * 0.1).collect();
+                vector[axis] += 1.0;
+                vectors.push(vector);
+            }
+        }
+        vectors
+    }
+
+    fn cluster(vectors: &[Vec<f32>], k: usize, seed: u64) -> Clustering {
+        let slices: Vec<&[f32]> = vectors.iter().map(|v| v.as_slice()).collect();
+        spherical_kmeans(&slices, k, 50, seed)
+    }
+
+    #[test]
+    fn separable_groups_end_up_in_their_own_clusters() {
+        let vectors = separable();
+        for seed in 0..5 {
+            let clustering = cluster(&vectors, 3, seed);
+            assert_eq!(clustering.centroids.len(), 3);
+            for group in clustering.assignments.chunks(10) {
+                assert!(group.iter().all(|&cluster| cluster == group[0]));
+            }
+            let mut clusters: Vec<usize> =
+                clustering.assignments.chunks(10).map(|g| g[0]).collect();
+            clusters.sort();
+            clusters.dedup();
+            assert_eq!(clusters.len(), 3, "seed {}", seed);
+
+            for centroid in &clustering.centroids {
+                assert!((dot(centroid, centroid) - 1.0).abs() < 1e-5);
+            }
+        }
+    }
+
+    #[test]
+    fn k_is_capped_at_the_number_of_vectors() {
+        let vectors = vec![vec![1.0, 0.0], vec![0.0, 1.0]];
+        let clustering = cluster(&vectors, 5, 1);
+        assert_eq!(clustering.centroids.len(), 2);
+        assert_ne!(clustering.assignments[0], clustering.assignments[1]);
+
+        let clustering = cluster(&[], 3, 1);
+        assert!(clustering.centroids.is_empty());
+        assert!(clustering.assignments.is_empty());
+    }
+
+    #[test]
+    fn clustering_is_deterministic_for_a_seed() {
+        let vectors = separable();
+        let first = cluster(&vectors, 4, 42);
+        let second = cluster(&vectors, 4, 42);
+        assert_eq!(first.assignments, second.assignments);
+        assert_eq!(first.centroids, second.centroids);
+    }
+
+    #[test]
+    fn duplicate_vectors_leave_no_degenerate_centroids() {
+        // Two distinct points but three clusters forces an empty cluster
+        let vectors = vec![
+            vec![1.0, 0.0],
+            vec![1.0, 0.0],
+            vec![1.0, 0.0],
+            vec![0.0, 1.0],
+            vec![0.0, 1.0],
+        ];
+        for seed in 0..5 {
+            let clustering = cluster(&vectors, 3, seed);
+            assert_eq!(clustering.centroids.len(), 3);
+            assert_ne!(clustering.assignments[0], clustering.assignments[3]);
+            for centroid in &clustering.centroids {
+                assert!((dot(centroid, centroid) - 1.0).abs() < 1e-5);
+            }
+        }
+    }
+}
@@ -0,0 +1,98 @@
+use super::vector::cosine_similarity;
+
+/// Re-ranks candidates with maximal marginal relevance, returning the indices
+/// of up to `k` candidates in selection order.
+///
+/// Each step picks the candidate maximizing
+/// `(1 - diversity) * sim(query, c) - diversity * max(sim(c, selected))`, so a
+/// diversity of 0 keeps the pure relevance order and higher values increasingly
+/// penalize candidates close to ones already chosen.
+pub fn maximal_marginal_relevance(
+    query: &[f32],
+    candidates: &[&[f32]],
+    k: usize,
+    diversity: f32,
+) -> Vec<usize> {
+    let diversity = diversity.clamp(0.0, 1.0);
+    let relevance: Vec<f32> = candidates
+        .iter()
+        .map(|candidate| cosine_similarity(query, candidate))
+        .collect();
+    // Highest similarity of each candidate to any selected candidate so far
+    let mut redundancy: Vec<Option<f32>> = vec![None; candidates.len()];
+    let mut remaining: Vec<usize> = (0..candidates.len()).collect();
+    let mut selected = Vec::with_capacity(k.min(candidates.len()));
+
+    while selected.len() < k && !remaining.is_empty() {
+        let score = |index: usize| {
+            (1.0 - diversity) * relevance[index] - diversity * redundancy[index].unwrap_or(0.0)
+        };
+        let (position, &best) = remaining
+            .iter()
+            .enumerate()
+            .max_by(|(_, &a), (_, &b)| score(a).total_cmp(&score(b)).then(b.cmp(&a)))
+            .expect("remaining is not empty");
+        remaining.swap_remove(position);
+        selected.push(best);
+
+        for &index in &remaining {
+            let similarity = cosine_similarity(candidates[index], candidates[best]);
+            redundancy[index] = Some(redundancy[index].map_or(similarity, |r| r.max(similarity)));
+        }
+    }
+
+    selected
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const QUERY: [f32; 2] = [1.0, 0.3];
+    // The first two candidates are near duplicates, the third points elsewhere
+    const CANDIDATES: [&[f32]; 3] = [&[1.0, 0.0], &[1.0, 0.02], &[0.5, 1.0]];
+
+    #[test]
+    fn zero_diversity_keeps_relevance_order() {
+        assert_eq!(
+            maximal_marginal_relevance(&QUERY, &CANDIDATES, 3, 0.0),
+            vec![1, 0, 2]
+        );
+    }
+
+    #[test]
+    fn near_duplicates_are_suppressed() {
+        assert_eq!(
+            maximal_marginal_relevance(&QUERY, &CANDIDATES, 3, 0.5),
+            vec![1, 2, 0]
+        );
+        assert_eq!(
+            maximal_marginal_relevance(&QUERY, &CANDIDATES, 2, 0.5),
+            vec![1, 2]
+        );
+    }
+
+    #[test]
+    fn full_diversity_ignores_relevance() {
+        // Every first pick scores zero, so the earliest candidate wins the tie
+        assert_eq!(
+            maximal_marginal_relevance(&QUERY, &CANDIDATES, 3, 1.0),
+            vec![0, 2, 1]
+        );
+        // Out of range diversities are clamped
+        assert_eq!(
+            maximal_marginal_relevance(&QUERY, &CANDIDATES, 3, 7.0),
+            vec![0, 2, 1]
+        );
+    }
+
+    #[test]
+    fn k_is_capped_by_the_candidate_count() {
+        assert_eq!(
+            maximal_marginal_relevance(&QUERY, &CANDIDATES, 10, 0.3).len(),
+            3
+        );
+        assert!(maximal_marginal_relevance(&QUERY, &CANDIDATES, 0, 0.3).is_empty());
+        assert!(maximal_marginal_relevance(&QUERY, &[], 5, 0.3).is_empty());
+    }
+}
@@ -0,0 +1,8 @@
+//! Ranking and vector math shared by the similarity features.
+
+pub mod ann;
+pub mod fusion;
+pub mod kmeans;
+pub mod mmr;
+pub mod pca;
+pub mod vector;
@@ -0,0 +1,195 @@
+use super::vector::{dot, normalize};
+
+/// Vectors projected onto their first two principal components
+#[derive(Debug, Clone)]
+pub struct Projection {
+    /// Coordinates of each input vector
+    pub coordinates: Vec<[f32; 2]>,
+    /// Fraction of the total variance captured by each component
+    pub explained_variance: [f32; 2],
+}
+
+/// Projects vectors onto their first two principal components, found by
+/// power iteration on the covariance matrix of the centered vectors. Each
+/// component's sign is chosen so its largest coordinate is positive, making
+/// the layout stable across runs.
+pub fn project_2d(vectors: &[&[f32]], max_iterations: usize) -> Projection {
+    let Some(dimension) = vectors.first().map(|v| v.len()) else {
+        return Projection {
+            coordinates: Vec::new(),
+            explained_variance: [0.0; 2],
+        };
+    };
+
+    let mut mean = vec![0.0f32; dimension];
+    for vector in vectors {
+        mean.iter_mut().zip(*vector).for_each(|(m, v)| *m += v);
+    }
+    mean.iter_mut().for_each(|m| *m /= vectors.len() as f32);
+    let centered: Vec<Vec<f32>> = vectors
+        .iter()
+        .map(|vector| vector.iter().zip(&mean).map(|(v, m)| v - m).collect())
+        .collect();
+
+    let total_variance: f32 = centered.iter().map(|v| dot(v, v)).sum();
+    let mut components: Vec<Vec<f32>> = Vec::with_capacity(2);
+    let mut variances = [0.0f32; 2];
+    for (index, variance) in variances.iter_mut().enumerate() {
+        let (component, component_variance) =
+            principal_component(&centered, &components, dimension, index, max_iterations);
+        *variance = component_variance;
+        components.push(component);
+    }
+
+    let coordinates = centered
+        .iter()
+        .map(|vector| [dot(vector, &components[0]), dot(vector, &components[1])])
+        .collect();
+    let explained_variance = if total_variance > 0.0 {
+        variances.map(|variance| variance / total_variance)
+    } else {
+        [0.0; 2]
+    };
+
+    Projection {
+        coordinates,
+        explained_variance,
+    }
+}
+
+/// Leading eigenvector of the covariance matrix orthogonal to the components
+/// found so far, with the variance along it
+fn principal_component(
+    centered: &[Vec<f32>],
+    previous: &[Vec<f32>],
+    dimension: usize,
+    index: usize,
+    max_iterations: usize,
+) -> (Vec<f32>, f32) {
+    // Deterministic start that is unlikely to be orthogonal to the answer
+    let mut component: Vec<f32> = (0..dimension)
+        .map(|i| 1.0 + ((i + index) % 7) as f32 * 0.1)
+        .collect();
+    orthogonalize(&mut component, previous);
+    normalize(&mut component);
+
+    for _ in 0..max_iterations.max(1) {
+        // Covariance times the component, without forming the matrix
+        let mut next = vec![0.0f32; dimension];
+        for vector in centered {
+            let projection = dot(vector, &component);
+            next.iter_mut()
+                .zip(vector)
+                .for_each(|(n, v)| *n += projection * v);
+        }
+        orthogonalize(&mut next, previous);
+        normalize(&mut next);
+
+        let converged = dot(&next, &component).abs() > 1.0 - 1e-6;
+        component = next;
+        if converged {
+            break;
+        }
+    }
+
+    let largest = component
+        .iter()
+        .copied()
+        .max_by(|a, b| a.abs().total_cmp(&b.abs()))
+        .unwrap_or(0.0);
+    if largest < 0.0 {
+        component.iter_mut().for_each(|c| *c = -*c);
+    }
+
+    let variance = centered
+        .iter()
+        .map(|vector| {
+            let projection = dot(vector, &component);
+            projection * projection
+        })
+        .sum();
+    (component, variance)
+}
+
+/// Removes the parts of `vector` along each of the (unit-length) `basis`
+/// vectors
+fn orthogonalize(vector: &mut [f32], basis: &[Vec<f32>]) {
+    for direction in basis {
+        let projection = dot(vector, direction);
+        vector
+            .iter_mut()
+            .zip(direction)
+            .for_each(|(v, d)| *v -= projection * d);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn project(vectors: &[Vec<f32>]) -> Projection {
+        let slices: Vec<&[f32]> = vectors.iter().map(|v| v.as_slice()).collect();
+        project_2d(&slices, 100)
+    }
+
+    fn assert_close(actual: f32, expected: f32) {
+        assert!(
+            (actual - expected).abs() < 1e-3,
+            "expected {}, got {}",
+            expected,
+            actual
+        );
+    }
+
+    #[test]
+    fn first_component_follows_the_dominant_axis() {
+        // Spread 10 along x, 1 along y, uncorrelated; offset to test centering
+        let vectors = vec![
+            vec![-10.0, 1.0, 3.0],
+            vec![10.0, 1.0, 3.0],
+            vec![-5.0, -1.0, 3.0],
+            vec![5.0, -1.0, 3.0],
+        ];
+        let projection = project(&vectors);
+
+        let expected = [[-10.0, 1.0], [10.0, 1.0], [-5.0, -1.0], [5.0, -1.0]];
+        for (actual, expected) in projection.coordinates.iter().zip(expected) {
+            assert_close(actual[0], expected[0]);
+            assert_close(actual[1], expected[1]);
+        }
+        assert_close(projection.explained_variance[0], 250.0 / 254.0);
+        assert_close(projection.explained_variance[1], 4.0 / 254.0);
+    }
+
+    #[test]
+    fn collinear_vectors_have_no_second_component() {
+        let vectors = vec![vec![1.0, 2.0], vec![2.0, 4.0], vec![3.0, 6.0]];
+        let projection = project(&vectors);
+
+        assert_close(projection.explained_variance[0], 1.0);
+        assert_close(projection.explained_variance[1], 0.0);
+        let first: Vec<f32> = projection.coordinates.iter().map(|c| c[0]).collect();
+        assert_close(first[2] - first[0], 2.0 * 5.0f32.sqrt());
+        for coordinates in &projection.coordinates {
+            assert_close(coordinates[1], 0.0);
+        }
+    }
+
+    #[test]
+    fn identical_vectors_project_to_the_origin() {
+        let projection = project(&vec![vec![0.5, 0.5, 0.5]; 3]);
+        assert_eq!(projection.coordinates, vec![[0.0, 0.0]; 3]);
+        assert_eq!(projection.explained_variance, [0.0, 0.0]);
+    }
+
+    #[test]
+    fn zero_or_one_vector() {
+        let projection = project(&[]);
+        assert!(projection.coordinates.is_empty());
+        assert_eq!(projection.explained_variance, [0.0, 0.0]);
+
+        let projection = project(&[vec![1.0, -2.0, 3.0]]);
+        assert_eq!(projection.coordinates, vec![[0.0, 0.0]]);
+        assert_eq!(projection.explained_variance, [0.0, 0.0]);
+    }
+}
@@ -0,0 +1,39 @@
+/// Scales a vector to unit length in place. Zero vectors are left unchanged.
+pub fn normalize(vector: &mut [f32]) {
+    let norm = vector.iter().map(|v| v * v).sum::<f32>().sqrt();
+    if norm > 0.0 {
+        vector.iter_mut().for_each(|v| *v /= norm);
+    }
+}
+
+pub fn dot(a: &[f32], b: &[f32]) -> f32 {
+    a.iter().zip(b).map(|(a, b)| a * b).sum()
+}
+
+/// Cosine similarity in [-1, 1], or 0 when either vector is zero
+pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    let norms = dot(a, a).sqrt() * dot(b, b).sqrt();
+    if norms == 0.0 {
+        0.0
+    } else {
+        dot(a, b) / norms
+    }
+}
+
+/// Unit-length mean direction of the given vectors, each normalized first so
+/// that every vector contributes equally. Returns `None` for no vectors.
+pub fn mean_direction<'a>(vectors: impl IntoIterator<Item = &'a [f32]>) -> Option<Vec<f32>> {
+    let mut sum: Option<Vec<f32>> = None;
+    for vector in vectors {
+        let mut unit = vector.to_vec();
+        normalize(&mut unit);
+        match sum.as_mut() {
+            Some(sum) => sum.iter_mut().zip(&unit).for_each(|(s, v)| *s += v),
+            None => sum = Some(unit),
+        }
+    }
+    sum.map(|mut sum| {
+        normalize(&mut sum);
+        sum
+    })
+}